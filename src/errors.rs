@@ -10,9 +10,33 @@ pub enum AppError {
     #[error("Invalid path: {0}")]
     InvalidPath(String),
 
+    #[error("Cannot read the workshop folder {0}: {1}")]
+    WorkshopUnreadable(String, String),
+
     #[error("Mod meta.cpp missing or unreadable for mod ID {0}")]
     MissingMeta(u64),
 
+    #[error("Cannot hardlink across filesystems: {0}")]
+    CrossFilesystem(String),
+
+    #[error("Case collision between {0} and {1}")]
+    CaseCollision(String, String),
+
+    #[error("Invalid PBO: {0}")]
+    InvalidPbo(String),
+
+    #[error("Invalid signature file: {0}")]
+    InvalidSignature(String),
+
+    #[error("Invalid value for {0}: {1}")]
+    InvalidCfgValue(String, String),
+
+    #[error("Config parse error: {0}")]
+    CfgParseError(String),
+
+    #[error("Steam manifest parse error: {0}")]
+    VdfParseError(String),
+
     #[error("Path conversion error: {0}")]
     PathConversionError(String),
 
@@ -22,6 +46,36 @@ pub enum AppError {
     #[error("JSON error: {0}")]
     SerdeError(#[from] serde_json::Error),
 
+    #[error("Process exited with {0}, see {1}")]
+    ProcessExited(String, String),
+
+    #[error("Cannot watch the workshop folder: {0}")]
+    WatchError(String),
+
+    #[error("Invalid key in keymap: {0}")]
+    InvalidKey(String),
+
+    #[error("Keymap conflict: {0}")]
+    KeymapConflict(String),
+
+    #[error("Unknown theme: {0}, use dark, light or mono")]
+    InvalidTheme(String),
+
+    #[error("Invalid colour in theme: {0}")]
+    InvalidColor(String),
+
+    #[error("Invalid pattern: {0}")]
+    InvalidPattern(String),
+
+    #[error("Server query failed: {0}")]
+    QueryError(String),
+
+    #[error("A saved server named {0} already exists")]
+    NameTaken(String),
+
+    #[error("Game is already running")]
+    AlreadyRunning,
+
     #[error("Unsupported platform")]
     UnsupportedPlatform,
 }
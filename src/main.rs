@@ -6,7 +6,7 @@ mod errors;
 mod mod_manager;
 
 fn main() -> AppResult<()> {
-    let mut manager = ModManager::new(15)?;
+    let mut manager = ModManager::new()?;
 
     manager.start()?;
 
@@ -0,0 +1,557 @@
+use std::{
+    collections::BTreeMap,
+    io::{Cursor, Read},
+    net::{ToSocketAddrs, UdpSocket},
+    time::Duration,
+};
+
+use crate::errors::{AppError, AppResult};
+
+use super::utils;
+
+const SINGLE_PACKET: i32 = -1;
+const SPLIT_PACKET: i32 = -2;
+
+const A2S_INFO: u8 = 0x54;
+const A2S_INFO_RESPONSE: u8 = 0x49;
+const A2S_RULES: u8 = 0x56;
+const A2S_RULES_RESPONSE: u8 = 0x45;
+const CHALLENGE_RESPONSE: u8 = 0x41;
+
+const MAX_PACKET_SIZE: usize = 1400;
+
+#[derive(Debug, Clone)]
+pub struct ServerInfo {
+    pub name: String,
+    pub map: String,
+    // Arma 3 reports the mission in the game field
+    pub mission: String,
+    pub players: u8,
+    pub max_players: u8,
+    pub version: String,
+    pub password: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct RequiredMod {
+    pub name: String,
+    pub workshop_id: u64,
+    pub hash: u32,
+    pub dlc: bool,
+}
+
+// The mod list and signature keys Arma 3 encodes into the rules
+#[derive(Debug, Clone, Default)]
+pub struct ArmaRules {
+    pub mods: Vec<RequiredMod>,
+    pub signatures: Vec<String>,
+}
+
+pub struct Client {
+    socket: UdpSocket,
+}
+
+impl Client {
+    // Arma 3 answers queries on the game port + 1 by default
+    pub fn connect(host: &str, query_port: u16, timeout: Duration) -> AppResult<Client> {
+        let address = (host, query_port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| AppError::QueryError(format!("Cannot resolve {}", host)))?;
+
+        let socket = UdpSocket::bind(if address.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        })?;
+        socket.set_read_timeout(Some(timeout))?;
+        socket.connect(address)?;
+
+        Ok(Client { socket })
+    }
+
+    pub fn info(&self) -> AppResult<ServerInfo> {
+        let mut request = vec![0xff, 0xff, 0xff, 0xff, A2S_INFO];
+        request.extend(b"Source Engine Query\0");
+
+        let mut response = self.request(&request)?;
+
+        // Servers may ask for the challenge to be appended to the request
+        if response.first() == Some(&CHALLENGE_RESPONSE) {
+            request.extend(&response[1..5.min(response.len())]);
+            response = self.request(&request)?;
+        }
+
+        parse_info(&response)
+    }
+
+    pub fn rules(&self) -> AppResult<ArmaRules> {
+        let mut request = vec![0xff, 0xff, 0xff, 0xff, A2S_RULES, 0xff, 0xff, 0xff, 0xff];
+
+        let mut response = self.request(&request)?;
+
+        if response.first() == Some(&CHALLENGE_RESPONSE) {
+            request.truncate(5);
+            request.extend(&response[1..5.min(response.len())]);
+            response = self.request(&request)?;
+        }
+
+        parse_arma_rules(&parse_rules(&response)?)
+    }
+
+    // Sends the request and returns the payload after the packet header, joining split packets
+    fn request(&self, request: &[u8]) -> AppResult<Vec<u8>> {
+        self.socket.send(request)?;
+
+        let mut parts: BTreeMap<u8, Vec<u8>> = BTreeMap::new();
+
+        loop {
+            let mut buf = [0; MAX_PACKET_SIZE];
+            let len = self.socket.recv(&mut buf).map_err(|e| {
+                AppError::QueryError(format!("No response from the server ({})", e))
+            })?;
+            let mut reader = Cursor::new(&buf[..len]);
+
+            match read_i32(&mut reader)? {
+                SINGLE_PACKET => return Ok(buf[4..len].to_vec()),
+                SPLIT_PACKET => {
+                    let id = read_i32(&mut reader)?;
+                    if id as u32 & 0x8000_0000 != 0 {
+                        return Err(AppError::QueryError(
+                            "Compressed responses are not supported".to_string(),
+                        ));
+                    }
+
+                    let total = read_u8(&mut reader)?;
+                    let number = read_u8(&mut reader)?;
+                    read_u16(&mut reader)?;
+
+                    parts.insert(number, buf[reader.position() as usize..len].to_vec());
+
+                    if parts.len() == total as usize {
+                        let payload = parts.into_values().flatten().collect::<Vec<_>>();
+
+                        // The joined payload starts with its own single packet header
+                        return match payload.get(..4) {
+                            Some([0xff, 0xff, 0xff, 0xff]) => Ok(payload[4..].to_vec()),
+                            _ => Err(invalid_response()),
+                        };
+                    }
+                }
+                _ => return Err(invalid_response()),
+            }
+        }
+    }
+}
+
+fn parse_info(data: &[u8]) -> AppResult<ServerInfo> {
+    let mut reader = Cursor::new(data);
+
+    if read_u8(&mut reader)? != A2S_INFO_RESPONSE {
+        return Err(invalid_response());
+    }
+
+    // Protocol
+    read_u8(&mut reader)?;
+    let name = read_string(&mut reader)?;
+    let map = read_string(&mut reader)?;
+    // Folder
+    read_string(&mut reader)?;
+    let mission = read_string(&mut reader)?;
+    // Steam app ID
+    read_u16(&mut reader)?;
+    let players = read_u8(&mut reader)?;
+    let max_players = read_u8(&mut reader)?;
+    // Bots, server type and environment
+    let mut skipped = [0; 3];
+    reader.read_exact(&mut skipped)?;
+    let password = read_u8(&mut reader)? == 1;
+    // VAC
+    read_u8(&mut reader)?;
+    let version = read_string(&mut reader)?;
+
+    Ok(ServerInfo {
+        name,
+        map,
+        mission,
+        players,
+        max_players,
+        version,
+        password,
+    })
+}
+
+// The rules as raw name and value bytes, Arma 3's binary names aren't valid UTF-8
+fn parse_rules(data: &[u8]) -> AppResult<Vec<(Vec<u8>, Vec<u8>)>> {
+    let mut reader = Cursor::new(data);
+
+    if read_u8(&mut reader)? != A2S_RULES_RESPONSE {
+        return Err(invalid_response());
+    }
+
+    let count = read_u16(&mut reader)?;
+    let mut rules = Vec::new();
+
+    for _ in 0..count {
+        let name = read_bytes_until_nul(&mut reader)?;
+        let value = read_bytes_until_nul(&mut reader)?;
+        rules.push((name, value));
+    }
+
+    Ok(rules)
+}
+
+// Arma 3 splits its binary payload over rules named by two bytes, the chunk number
+// starting at 1 and the number of chunks
+fn parse_arma_rules(rules: &[(Vec<u8>, Vec<u8>)]) -> AppResult<ArmaRules> {
+    let mut chunks = rules
+        .iter()
+        .filter(|(name, _)| name.len() == 2 && name[0] >= 1 && name[0] <= name[1])
+        .map(|(name, value)| (name[0], value))
+        .collect::<Vec<_>>();
+
+    if chunks.is_empty() {
+        return Ok(ArmaRules::default());
+    }
+
+    chunks.sort_by_key(|(number, _)| *number);
+
+    let payload = unescape(
+        &chunks
+            .into_iter()
+            .flat_map(|(_, value)| value.iter().copied())
+            .collect::<Vec<_>>(),
+    );
+
+    parse_arma_payload(&payload)
+}
+
+fn parse_arma_payload(payload: &[u8]) -> AppResult<ArmaRules> {
+    let mut reader = Cursor::new(payload);
+
+    // Protocol version and overflow flags
+    read_u8(&mut reader)?;
+    read_u8(&mut reader)?;
+
+    // Every owned DLC flag is followed by its hash
+    let dlc_flags = read_u16(&mut reader)?;
+    for _ in 0..dlc_flags.count_ones() {
+        read_u32(&mut reader)?;
+    }
+
+    // Difficulty and crosshair
+    read_u8(&mut reader)?;
+    read_u8(&mut reader)?;
+
+    let mut rules = ArmaRules::default();
+
+    let mod_count = read_u8(&mut reader)?;
+    for _ in 0..mod_count {
+        let hash = read_u32(&mut reader)?;
+        let flags = read_u8(&mut reader)?;
+
+        let mut id_bytes = [0; 8];
+        let id_len = (flags & 0x0f) as usize;
+        reader.read_exact(&mut id_bytes[..id_len.min(8)])?;
+
+        let name = read_pascal_string(&mut reader)?;
+
+        rules.mods.push(RequiredMod {
+            name,
+            workshop_id: u64::from_le_bytes(id_bytes),
+            hash,
+            dlc: flags & 0x10 != 0,
+        });
+    }
+
+    // Older servers end the payload after the mods
+    if let Ok(signature_count) = read_u8(&mut reader) {
+        for _ in 0..signature_count {
+            rules.signatures.push(read_pascal_string(&mut reader)?);
+        }
+    }
+
+    Ok(rules)
+}
+
+// 0x01 escapes the bytes that can't be sent in a rule value
+fn unescape(data: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(data.len());
+    let mut bytes = data.iter();
+
+    while let Some(&byte) = bytes.next() {
+        if byte != 0x01 {
+            result.push(byte);
+            continue;
+        }
+
+        match bytes.next() {
+            Some(0x01) => result.push(0x01),
+            Some(0x02) => result.push(0x00),
+            Some(0x03) => result.push(0xff),
+            Some(&other) => result.extend([0x01, other]),
+            None => result.push(0x01),
+        }
+    }
+
+    result
+}
+
+fn invalid_response() -> AppError {
+    AppError::QueryError("Invalid response from the server".to_string())
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> AppResult<u8> {
+    let mut buf = [0; 1];
+    reader.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_u16<R: Read>(reader: &mut R) -> AppResult<u16> {
+    let mut buf = [0; 2];
+    reader.read_exact(&mut buf)?;
+    Ok(u16::from_le_bytes(buf))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> AppResult<u32> {
+    let mut buf = [0; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_i32<R: Read>(reader: &mut R) -> AppResult<i32> {
+    let mut buf = [0; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(i32::from_le_bytes(buf))
+}
+
+fn read_bytes_until_nul<R: Read>(reader: &mut R) -> AppResult<Vec<u8>> {
+    let mut bytes = Vec::new();
+
+    loop {
+        match read_u8(reader)? {
+            0 => return Ok(bytes),
+            byte => bytes.push(byte),
+        }
+    }
+}
+
+fn read_string<R: Read>(reader: &mut R) -> AppResult<String> {
+    Ok(utils::sanitize_name(&String::from_utf8_lossy(
+        &read_bytes_until_nul(reader)?,
+    )))
+}
+
+fn read_pascal_string<R: Read>(reader: &mut R) -> AppResult<String> {
+    let len = read_u8(reader)? as usize;
+    let mut buf = vec![0; len];
+    reader.read_exact(&mut buf)?;
+    Ok(utils::sanitize_name(&String::from_utf8_lossy(&buf)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    const HEADER: [u8; 4] = [0xff, 0xff, 0xff, 0xff];
+
+    // Reverses unescape, the way the server encodes its payload
+    fn escape(data: &[u8]) -> Vec<u8> {
+        data.iter()
+            .flat_map(|&byte| match byte {
+                0x01 => vec![0x01, 0x01],
+                0x00 => vec![0x01, 0x02],
+                0xff => vec![0x01, 0x03],
+                byte => vec![byte],
+            })
+            .collect()
+    }
+
+    // A payload with two mods, one of them a DLC, and one signature key
+    fn arma_payload() -> Vec<u8> {
+        let mut payload = vec![3, 0];
+        // One DLC flag, followed by its hash
+        payload.extend(1u16.to_le_bytes());
+        payload.extend(0xdead_beefu32.to_le_bytes());
+        // Difficulty and crosshair
+        payload.extend([2, 0]);
+
+        payload.push(2);
+
+        payload.extend(0x0102_00ffu32.to_le_bytes());
+        payload.push(4);
+        payload.extend(463939057u32.to_le_bytes());
+        payload.push(4);
+        payload.extend(b"ACE3");
+
+        payload.extend(7u32.to_le_bytes());
+        payload.push(0x10 | 3);
+        payload.extend(&288520u32.to_le_bytes()[..3]);
+        payload.push(3);
+        payload.extend(b"DLC");
+
+        payload.push(1);
+        payload.push(3);
+        payload.extend(b"ace");
+
+        payload
+    }
+
+    fn check_arma_rules(rules: &ArmaRules) {
+        assert_eq!(rules.mods.len(), 2);
+
+        assert_eq!(rules.mods[0].name, "ACE3");
+        assert_eq!(rules.mods[0].workshop_id, 463939057);
+        assert_eq!(rules.mods[0].hash, 0x0102_00ff);
+        assert!(!rules.mods[0].dlc);
+
+        assert_eq!(rules.mods[1].workshop_id, 288520);
+        assert!(rules.mods[1].dlc);
+
+        assert_eq!(rules.signatures, vec!["ace".to_string()]);
+    }
+
+    // The escaped payload split over rules named by the chunk number and count
+    fn arma_rule_chunks(count: u8) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let escaped = escape(&arma_payload());
+        let size = escaped.len().div_ceil(count as usize);
+
+        escaped
+            .chunks(size)
+            .enumerate()
+            .map(|(i, chunk)| (vec![i as u8 + 1, count], chunk.to_vec()))
+            .collect()
+    }
+
+    fn rules_response(rules: &[(Vec<u8>, Vec<u8>)]) -> Vec<u8> {
+        let mut response = HEADER.to_vec();
+        response.push(A2S_RULES_RESPONSE);
+        response.extend((rules.len() as u16).to_le_bytes());
+
+        for (name, value) in rules {
+            response.extend(name);
+            response.push(0);
+            response.extend(value);
+            response.push(0);
+        }
+
+        response
+    }
+
+    fn info_response() -> Vec<u8> {
+        let mut response = HEADER.to_vec();
+        response.extend([A2S_INFO_RESPONSE, 17]);
+        for field in ["Test Server", "Altis", "Arma3", "Domination"] {
+            response.extend(field.as_bytes());
+            response.push(0);
+        }
+        // Steam app ID, Arma 3's is too large for the field
+        response.extend([0, 0]);
+        // Players, max players, bots, server type, environment, password and VAC
+        response.extend([5, 40, 0, b'd', b'l', 1, 0]);
+        response.extend(b"2.18.152405\0");
+
+        response
+    }
+
+    #[test]
+    fn unescapes_rule_values() {
+        assert_eq!(
+            unescape(&[0x41, 0x01, 0x01, 0x01, 0x02, 0x01, 0x03, 0x42]),
+            vec![0x41, 0x01, 0x00, 0xff, 0x42]
+        );
+
+        // Unknown escapes and a trailing escape byte are kept as they are
+        assert_eq!(unescape(&[0x01, 0x05, 0x01]), vec![0x01, 0x05, 0x01]);
+    }
+
+    #[test]
+    fn parses_rules_in_any_order() {
+        let mut rules = arma_rule_chunks(3);
+        rules.reverse();
+        // Rules that aren't chunks are left out
+        rules.insert(1, (b"allowedVoteCmds".to_vec(), b"1".to_vec()));
+
+        check_arma_rules(&parse_arma_rules(&rules).unwrap());
+    }
+
+    #[test]
+    fn parses_servers_without_rules() {
+        let rules = parse_arma_rules(&[(b"other".to_vec(), b"1".to_vec())]).unwrap();
+
+        assert!(rules.mods.is_empty());
+        assert!(rules.signatures.is_empty());
+    }
+
+    #[test]
+    fn rejects_truncated_payloads() {
+        let escaped = escape(&arma_payload());
+        let rules = [(vec![1, 1], escaped[..escaped.len() / 2].to_vec())];
+
+        assert!(parse_arma_rules(&rules).is_err());
+    }
+
+    // Answers each request with a challenge first, and the rules in split packets sent out
+    // of order
+    #[test]
+    fn queries_a_server() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        server
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let port = server.local_addr().unwrap().port();
+
+        let handle = thread::spawn(move || {
+            let challenge = [HEADER.as_slice(), &[CHALLENGE_RESPONSE, 1, 2, 3, 4]].concat();
+            let mut buf = [0; MAX_PACKET_SIZE];
+
+            let (len, client) = server.recv_from(&mut buf).unwrap();
+            assert_eq!(buf[4], A2S_INFO);
+            assert!(!buf[..len].ends_with(&[1, 2, 3, 4]));
+            server.send_to(&challenge, client).unwrap();
+
+            let (len, _) = server.recv_from(&mut buf).unwrap();
+            assert!(buf[..len].ends_with(&[1, 2, 3, 4]));
+            server.send_to(&info_response(), client).unwrap();
+
+            let (_, _) = server.recv_from(&mut buf).unwrap();
+            assert_eq!(buf[4], A2S_RULES);
+            server.send_to(&challenge, client).unwrap();
+
+            let (len, _) = server.recv_from(&mut buf).unwrap();
+            assert_eq!(
+                &buf[..len],
+                &[0xff, 0xff, 0xff, 0xff, A2S_RULES, 1, 2, 3, 4]
+            );
+
+            let response = rules_response(&arma_rule_chunks(2));
+            let parts = response.chunks(response.len() / 3 + 1).collect::<Vec<_>>();
+
+            for number in [2, 0, 1] {
+                let mut packet = SPLIT_PACKET.to_le_bytes().to_vec();
+                packet.extend(7i32.to_le_bytes());
+                packet.extend([parts.len() as u8, number as u8]);
+                packet.extend((MAX_PACKET_SIZE as u16).to_le_bytes());
+                packet.extend(parts[number]);
+
+                server.send_to(&packet, client).unwrap();
+            }
+        });
+
+        let client = Client::connect("127.0.0.1", port, Duration::from_secs(5)).unwrap();
+
+        let info = client.info().unwrap();
+        assert_eq!(info.name, "Test Server");
+        assert_eq!(info.map, "Altis");
+        assert_eq!(info.mission, "Domination");
+        assert_eq!((info.players, info.max_players), (5, 40));
+        assert_eq!(info.version, "2.18.152405");
+        assert!(info.password);
+
+        check_arma_rules(&client.rules().unwrap());
+
+        handle.join().unwrap();
+    }
+}
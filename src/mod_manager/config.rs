@@ -1,12 +1,24 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
 use serde::{Deserialize, Serialize};
 
 use crate::errors::{AppError, AppResult};
 
-use super::utils;
+use super::{
+    connection::Connection,
+    deploy::DeployStrategy,
+    keymap::Action,
+    server::ServerProfile,
+    server_cfg::ServerPasswords,
+    theme::{Role, DEFAULT_THEME},
+    utils,
+};
 
 const SAVE_FILE: &str = "arma3-mod-manager-cli-config.json";
+const DEFAULT_HISTORY_LIMIT: usize = 100;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
@@ -14,6 +26,44 @@ pub struct Config {
     workshop_path: String,
     enabled_mods: Vec<u64>,
     default_args: String,
+    #[serde(default)]
+    deploy_strategy: DeployStrategy,
+    #[serde(default)]
+    lowercase_mods: bool,
+    #[serde(default)]
+    rpt_path: Option<String>,
+    #[serde(default)]
+    server_profiles: Vec<ServerProfile>,
+    #[serde(default)]
+    connections: Vec<Connection>,
+    // Save the enabled mods on every change instead of with <CTRL> + S
+    #[serde(default)]
+    autosave: bool,
+    // Flip through the mod list a page at a time instead of scrolling
+    #[serde(default)]
+    paged_list: bool,
+    // Tags given to mods in the mod list by mod ID
+    #[serde(default)]
+    tags: BTreeMap<u64, Vec<String>>,
+    // How many edits can be undone
+    #[serde(default)]
+    history_limit: Option<usize>,
+    // Keep the undo history in a file, so edits can be undone after a restart
+    #[serde(default)]
+    keep_history: bool,
+    // Keys for the mod list by action, replacing the default keys of each action listed
+    #[serde(default)]
+    keymap: BTreeMap<Action, Vec<String>>,
+    // Capture clicks and the scroll wheel in the mod list, on unless set to false, which keeps
+    // selecting text with the mouse working
+    #[serde(default)]
+    mouse: Option<bool>,
+    // dark, light or mono
+    #[serde(default)]
+    theme: Option<String>,
+    // Colours replacing the theme's by what they are used for
+    #[serde(default)]
+    colors: BTreeMap<Role, String>,
 }
 
 impl Config {
@@ -29,6 +79,20 @@ impl Config {
             workshop_path,
             enabled_mods: Vec::new(),
             default_args: "-noSplash -skipIntro -world=empty".to_string(),
+            deploy_strategy: DeployStrategy::default(),
+            lowercase_mods: false,
+            rpt_path: None,
+            server_profiles: Vec::new(),
+            connections: Vec::new(),
+            autosave: false,
+            paged_list: false,
+            tags: BTreeMap::new(),
+            history_limit: None,
+            keep_history: false,
+            keymap: BTreeMap::new(),
+            mouse: None,
+            theme: None,
+            colors: BTreeMap::new(),
         };
 
         new_config.valid()?;
@@ -72,16 +136,103 @@ impl Config {
         self.default_args = args;
     }
 
+    pub fn get_autosave(&self) -> bool {
+        self.autosave
+    }
+
+    pub fn get_paged_list(&self) -> bool {
+        self.paged_list
+    }
+
+    pub fn get_history_limit(&self) -> usize {
+        self.history_limit.unwrap_or(DEFAULT_HISTORY_LIMIT)
+    }
+
+    pub fn get_keep_history(&self) -> bool {
+        self.keep_history
+    }
+
+    pub fn get_keymap(&self) -> &BTreeMap<Action, Vec<String>> {
+        &self.keymap
+    }
+
+    pub fn get_mouse(&self) -> bool {
+        self.mouse.unwrap_or(true)
+    }
+
+    pub fn get_theme(&self) -> &str {
+        self.theme.as_deref().unwrap_or(DEFAULT_THEME)
+    }
+
+    pub fn get_colors(&self) -> &BTreeMap<Role, String> {
+        &self.colors
+    }
+
+    pub fn get_tags(&self, id: u64) -> &[String] {
+        self.tags.get(&id).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn add_tag(&mut self, id: u64, tag: &str) {
+        let tags = self.tags.entry(id).or_default();
+
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+            tags.sort();
+        }
+    }
+
+    pub fn remove_tag(&mut self, id: u64, tag: &str) {
+        if let Some(tags) = self.tags.get_mut(&id) {
+            tags.retain(|t| t != tag);
+
+            if tags.is_empty() {
+                self.tags.remove(&id);
+            }
+        }
+    }
+
+    pub fn get_deploy_strategy(&self) -> DeployStrategy {
+        self.deploy_strategy
+    }
+
+    pub fn get_lowercase_mods(&self) -> bool {
+        self.lowercase_mods
+    }
+
+    pub fn get_rpt_path(&self) -> Option<&str> {
+        self.rpt_path.as_deref()
+    }
+
+    pub fn get_server_profiles(&self) -> &[ServerProfile] {
+        &self.server_profiles
+    }
+
+    pub fn get_server_profiles_mut(&mut self) -> &mut Vec<ServerProfile> {
+        &mut self.server_profiles
+    }
+
+    pub fn get_connections(&self) -> &[Connection] {
+        &self.connections
+    }
+
+    pub fn get_connections_mut(&mut self) -> &mut Vec<Connection> {
+        &mut self.connections
+    }
+
     pub fn save(&self) -> AppResult<()> {
-        super::file_handler::write_json(&Config::get_save_path()?, &self)?;
+        // The passwords are written first, the config no longer holds them
+        ServerPasswords::from_profiles(&self.server_profiles).save()?;
+        super::file_handler::write_json(&Config::get_save_path()?, self)?;
         Ok(())
     }
 
     pub fn read() -> AppResult<Self> {
-        let config: Config = super::file_handler::read_json(&Config::get_save_path()?)?;
+        let mut config: Config = super::file_handler::read_json(&Config::get_save_path()?)?;
 
         config.valid()?;
 
+        ServerPasswords::read().apply(&mut config.server_profiles);
+
         Ok(config)
     }
 }
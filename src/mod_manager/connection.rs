@@ -0,0 +1,190 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{AppError, AppResult};
+
+use super::{file_handler, utils};
+
+const PASSWORDS_FILE: &str = "arma3-mod-manager-cli-passwords.json";
+
+// A server to join directly on launch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Connection {
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+    // Steam query port, the game port + 1 when not set
+    #[serde(default)]
+    pub query_port: Option<u16>,
+    // Mods the server requires
+    #[serde(default)]
+    pub mods: Vec<u64>,
+}
+
+impl Connection {
+    pub fn new(name: String) -> Self {
+        Connection {
+            name,
+            host: "127.0.0.1".to_string(),
+            port: 2302,
+            query_port: None,
+            mods: Vec::new(),
+        }
+    }
+
+    pub fn get_query_port(&self) -> u16 {
+        self.query_port.unwrap_or(self.port.saturating_add(1))
+    }
+
+    pub fn args(&self, password: Option<&str>) -> Vec<String> {
+        let mut args = vec![
+            format!("-connect={}", self.host),
+            format!("-port={}", self.port),
+        ];
+
+        if let Some(password) = password.filter(|p| !p.is_empty()) {
+            args.push(format!("-password={}", password));
+        }
+
+        args
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionField {
+    Name,
+    Host,
+    Port,
+    QueryPort,
+    Password,
+}
+
+impl ConnectionField {
+    pub const ALL: [ConnectionField; 5] = [
+        ConnectionField::Name,
+        ConnectionField::Host,
+        ConnectionField::Port,
+        ConnectionField::QueryPort,
+        ConnectionField::Password,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConnectionField::Name => "Name",
+            ConnectionField::Host => "Host",
+            ConnectionField::Port => "Port",
+            ConnectionField::QueryPort => "Query Port",
+            ConnectionField::Password => "Password",
+        }
+    }
+}
+
+// Server passwords by connection name
+//
+// Kept out of the config in a file only the user can read, so the config can be shared
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Passwords {
+    passwords: HashMap<String, String>,
+}
+
+impl Passwords {
+    fn get_save_path() -> AppResult<PathBuf> {
+        let home_path = utils::get_home_path()?;
+
+        Ok(Path::new(&home_path).join(PASSWORDS_FILE))
+    }
+
+    pub fn read() -> Passwords {
+        Passwords::get_save_path()
+            .and_then(|path| file_handler::read_json(&path))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> AppResult<()> {
+        file_handler::write_json_private(&Passwords::get_save_path()?, self)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.passwords.get(name).map(|p| p.as_str())
+    }
+
+    pub fn set(&mut self, name: &str, password: String) {
+        if password.is_empty() {
+            self.passwords.remove(name);
+        } else {
+            self.passwords.insert(name.to_string(), password);
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.passwords.remove(name);
+    }
+
+    pub fn rename(&mut self, from: &str, to: &str) {
+        if let Some(password) = self.passwords.remove(from) {
+            self.passwords.insert(to.to_string(), password);
+        }
+    }
+}
+
+// Reads the value of a form field, the password comes from the separate store
+pub fn get_field(connection: &Connection, passwords: &Passwords, field: ConnectionField) -> String {
+    match field {
+        ConnectionField::Name => connection.name.clone(),
+        ConnectionField::Host => connection.host.clone(),
+        ConnectionField::Port => connection.port.to_string(),
+        ConnectionField::QueryPort => connection
+            .query_port
+            .map(|p| p.to_string())
+            .unwrap_or_default(),
+        ConnectionField::Password => passwords.get(&connection.name).unwrap_or("").to_string(),
+    }
+}
+
+// The names of the other connections are passed in, since passwords are stored by name
+pub fn set_field(
+    connection: &mut Connection,
+    passwords: &mut Passwords,
+    taken: &[String],
+    field: ConnectionField,
+    value: &str,
+) -> AppResult<()> {
+    let invalid = || AppError::InvalidCfgValue(field.label().to_string(), value.to_string());
+
+    match field {
+        ConnectionField::Name => {
+            let name = value.trim();
+            if name.is_empty() {
+                return Err(invalid());
+            }
+            if taken.iter().any(|t| t == name) {
+                return Err(AppError::NameTaken(name.to_string()));
+            }
+
+            passwords.rename(&connection.name, name);
+            connection.name = name.to_string();
+        }
+        ConnectionField::Host => {
+            let host = value.trim();
+            if host.is_empty() || host.contains(char::is_whitespace) {
+                return Err(invalid());
+            }
+
+            connection.host = host.to_string();
+        }
+        ConnectionField::Port => {
+            connection.port = value.trim().parse().map_err(|_| invalid())?;
+        }
+        ConnectionField::QueryPort if value.trim().is_empty() => connection.query_port = None,
+        ConnectionField::QueryPort => {
+            connection.query_port = Some(value.trim().parse().map_err(|_| invalid())?);
+        }
+        ConnectionField::Password => passwords.set(&connection.name, value.to_string()),
+    }
+
+    Ok(())
+}
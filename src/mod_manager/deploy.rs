@@ -0,0 +1,292 @@
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    fs,
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+
+use crate::errors::{AppError, AppResult};
+
+use super::file_handler;
+
+const MANIFEST_FILE: &str = ".arma3-mod-manager-cli-deploy.json";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeployStrategy {
+    #[default]
+    Symlink,
+    Hardlink,
+    Copy,
+}
+
+// Keeps track of what was put into the game directory, so it can be removed again
+// without touching anything the user placed there themselves
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    strategy: DeployStrategy,
+    #[serde(default)]
+    lowercase: bool,
+    entries: Vec<String>,
+    // Fingerprint of each mod's contents at the time its tree was built
+    #[serde(default)]
+    fingerprints: HashMap<String, u64>,
+}
+
+impl Manifest {
+    fn path(game_path: &Path) -> PathBuf {
+        game_path.join(MANIFEST_FILE)
+    }
+
+    fn read(game_path: &Path) -> Option<Self> {
+        file_handler::read_json(&Manifest::path(game_path)).ok()
+    }
+
+    fn save(&self, game_path: &Path) -> AppResult<()> {
+        file_handler::write_json(&Manifest::path(game_path), self)
+    }
+}
+
+// Deploys the mods into the game directory using the given strategy
+//
+// With lowercase set, every mod gets a tree with lowercased file and folder names
+// instead of a link to the mod root. Anything deployed earlier that is no longer
+// part of mod_paths is removed
+pub fn deploy(
+    strategy: DeployStrategy,
+    lowercase: bool,
+    game_path: &Path,
+    mod_paths: Vec<PathBuf>,
+) -> AppResult<()> {
+    let names = mod_paths
+        .iter()
+        .map(|p| entry_name(p))
+        .collect::<AppResult<Vec<_>>>()?;
+
+    let mut fingerprints = HashMap::new();
+
+    match Manifest::read(game_path) {
+        Some(manifest) => {
+            let same_layout = manifest.strategy == strategy && manifest.lowercase == lowercase;
+
+            for entry in &manifest.entries {
+                // Trees are kept so they can be synced incrementally
+                if same_layout && names.contains(entry) {
+                    continue;
+                }
+                remove_entry(&game_path.join(entry))?;
+            }
+
+            if same_layout {
+                fingerprints = manifest.fingerprints;
+            }
+        }
+        // Older versions only created symlinks and did not keep a manifest
+        None => file_handler::remove_dir_symlinks(game_path)?,
+    }
+
+    // Recorded before anything is put into the game directory, so whatever a deploy that fails
+    // part-way leaves behind is still removed by the next one
+    let mut manifest = Manifest {
+        strategy,
+        lowercase,
+        entries: names.clone(),
+        fingerprints,
+    };
+
+    manifest.save(game_path)?;
+
+    if strategy == DeployStrategy::Symlink && !lowercase {
+        file_handler::create_sym_links(game_path, mod_paths)?;
+        manifest.fingerprints.clear();
+    } else {
+        if strategy == DeployStrategy::Hardlink {
+            for source in &mod_paths {
+                ensure_same_filesystem(source, game_path)?;
+            }
+        }
+
+        let mut new_fingerprints = HashMap::new();
+
+        for (source, name) in mod_paths.iter().zip(&names) {
+            let target = game_path.join(name);
+            let fingerprint = fingerprint(source)?;
+
+            // Only rebuild the tree when the mod's contents changed
+            if manifest.fingerprints.get(name) != Some(&fingerprint) || !target.is_dir() {
+                sync_tree(source, &target, strategy, lowercase)?;
+            }
+
+            new_fingerprints.insert(name.to_owned(), fingerprint);
+        }
+
+        manifest.fingerprints = new_fingerprints;
+    }
+
+    manifest.save(game_path)
+}
+
+fn entry_name(path: &Path) -> AppResult<String> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.to_string())
+        .ok_or_else(|| AppError::PathConversionError(path.to_string_lossy().into()))
+}
+
+fn remove_entry(path: &Path) -> AppResult<()> {
+    match path.symlink_metadata() {
+        Ok(meta) if meta.is_dir() => fs::remove_dir_all(path)?,
+        Ok(_) => fs::remove_file(path)?,
+        Err(_) => {}
+    }
+
+    Ok(())
+}
+
+fn ensure_same_filesystem(source: &Path, game_path: &Path) -> AppResult<()> {
+    if fs::metadata(source)?.dev() != fs::metadata(game_path)?.dev() {
+        return Err(AppError::CrossFilesystem(
+            source.to_string_lossy().to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+// Hashes the relative path, size and modification time of every file in the mod
+//
+// SHA-1 rather than the std hasher, whose output may change between Rust releases and would
+// make every tree rebuild after a toolchain upgrade
+fn fingerprint(source: &Path) -> AppResult<u64> {
+    fn walk(root: &Path, path: &Path, hasher: &mut Sha1) -> AppResult<()> {
+        let mut entries = fs::read_dir(path)?.collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(|e| e.file_name());
+
+        for entry in entries {
+            let path = entry.path();
+            let meta = fs::metadata(&path)?;
+
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            hasher.update(relative.to_string_lossy().as_bytes());
+            hasher.update([0]);
+
+            if meta.is_dir() {
+                walk(root, &path, hasher)?;
+            } else {
+                let modified = meta
+                    .modified()
+                    .ok()
+                    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                    .unwrap_or_default();
+
+                hasher.update(meta.len().to_le_bytes());
+                hasher.update(modified.as_nanos().to_le_bytes());
+            }
+        }
+
+        Ok(())
+    }
+
+    let mut hasher = Sha1::new();
+    walk(source, source, &mut hasher)?;
+
+    let digest = hasher.finalize();
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&digest[..8]);
+
+    Ok(u64::from_le_bytes(bytes))
+}
+
+// Mirrors the source tree into target, linking or copying each file
+//
+// Files that are already up to date are left alone, and files that no longer
+// exist in the source are removed
+fn sync_tree(
+    source: &Path,
+    target: &Path,
+    strategy: DeployStrategy,
+    lowercase: bool,
+) -> AppResult<()> {
+    if target.symlink_metadata().is_ok_and(|m| !m.is_dir()) {
+        fs::remove_file(target)?;
+    }
+    fs::create_dir_all(target)?;
+
+    let mut target_names: HashMap<OsString, PathBuf> = HashMap::new();
+
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let from = entry.path();
+
+        let name = if lowercase {
+            OsString::from(entry.file_name().to_string_lossy().to_lowercase())
+        } else {
+            entry.file_name()
+        };
+
+        // e.g. both Addons/ and addons/ in the same folder
+        if let Some(other) = target_names.get(&name) {
+            return Err(AppError::CaseCollision(
+                other.to_string_lossy().to_string(),
+                from.to_string_lossy().to_string(),
+            ));
+        }
+
+        let to = target.join(&name);
+        target_names.insert(name, from.clone());
+
+        // Follow links inside the mod, the game should see the files themselves
+        let from_meta = fs::metadata(&from)?;
+
+        if from_meta.is_dir() {
+            sync_tree(&from, &to, strategy, lowercase)?;
+            continue;
+        }
+
+        if let Ok(to_meta) = to.symlink_metadata() {
+            let up_to_date = match strategy {
+                DeployStrategy::Symlink => fs::read_link(&to).is_ok_and(|link| link == from),
+                DeployStrategy::Hardlink => {
+                    to_meta.dev() == from_meta.dev() && to_meta.ino() == from_meta.ino()
+                }
+                DeployStrategy::Copy => {
+                    to_meta.is_file()
+                        && to_meta.len() == from_meta.len()
+                        && to_meta.modified().ok() == from_meta.modified().ok()
+                }
+            };
+
+            if up_to_date {
+                continue;
+            }
+
+            remove_entry(&to)?;
+        }
+
+        match strategy {
+            DeployStrategy::Symlink => std::os::unix::fs::symlink(&from, &to)?,
+            DeployStrategy::Hardlink => fs::hard_link(&from, &to)?,
+            DeployStrategy::Copy => {
+                fs::copy(&from, &to)?;
+                fs::File::options()
+                    .write(true)
+                    .open(&to)?
+                    .set_modified(from_meta.modified()?)?;
+            }
+        }
+    }
+
+    for entry in fs::read_dir(target)? {
+        let entry = entry?;
+        if !target_names.contains_key(&entry.file_name()) {
+            remove_entry(&entry.path())?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use std::{collections::HashMap, path::Path};
+
+use regex::Regex;
+
+use super::{pbo, Mod};
+
+// Which installed mod provides each addon (CfgPatches class) and PBO prefix
+#[derive(Debug, Default)]
+pub struct AddonIndex {
+    patches: HashMap<String, u64>,
+    // Sorted longest first so the most specific prefix wins
+    prefixes: Vec<(String, u64)>,
+}
+
+impl AddonIndex {
+    pub fn build(mods: &[Mod], workshop_path: &Path) -> AddonIndex {
+        let mut index = AddonIndex::default();
+
+        for m in mods {
+            for pbo_path in pbo::find_pbos(&m.get_path(workshop_path)) {
+                // Unreadable PBOs are reported by the integrity check, not here
+                let Ok(pbo) = pbo::Pbo::read(&pbo_path) else {
+                    continue;
+                };
+
+                if let Some(prefix) = pbo.prefix() {
+                    index.prefixes.push((normalise_path(prefix), m.id));
+                }
+
+                for patch in pbo.cfg_patches().unwrap_or_default() {
+                    index.patches.entry(patch.to_lowercase()).or_insert(m.id);
+                }
+            }
+        }
+
+        index
+            .prefixes
+            .sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+
+        index
+    }
+
+    pub fn patch_provider(&self, patch: &str) -> Option<u64> {
+        self.patches.get(&patch.to_lowercase()).copied()
+    }
+
+    pub fn path_provider(&self, path: &str) -> Option<u64> {
+        let path = normalise_path(path);
+
+        self.prefixes
+            .iter()
+            .find(|(prefix, _)| {
+                path.strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('\\'))
+            })
+            .map(|(_, id)| *id)
+    }
+}
+
+fn normalise_path(path: &str) -> String {
+    path.replace('/', "\\")
+        .trim_start_matches('\\')
+        .trim_end_matches('\\')
+        .to_lowercase()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IssueKind {
+    // An addon requires another addon that is not loaded, needed_by is the mod of the addon
+    RequiresAddon {
+        addon: String,
+        required: String,
+        needed_by: Option<u64>,
+    },
+    // A CfgPatches entry was looked up but is not loaded
+    NoPatch(String),
+    // A file with a \prefix\... path could not be found
+    NoFile(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Issue {
+    pub kind: IssueKind,
+    // Installed mod that provides what is missing
+    pub provider: Option<u64>,
+}
+
+// Scans the log for missing addon and file errors and works out which mods are involved
+pub fn diagnose(lines: &[String], index: &AddonIndex) -> Vec<Issue> {
+    let requires = Regex::new(r"(?i)addon '([^']+)' requires addon '([^']+)'").unwrap();
+    let no_entry = Regex::new(r"(?i)no entry '[^']*config\.bin/cfgpatches\.([^'./]+)").unwrap();
+    let file_error = Regex::new(r"(?i)(cannot|can't|not found|missing|unable)").unwrap();
+    let file_path = Regex::new(r"\\?((?:[\w@.\-]+\\)+[\w@.\-]+)").unwrap();
+
+    let mut issues: Vec<Issue> = Vec::new();
+
+    for line in lines {
+        let (kind, provider) = if let Some(caps) = requires.captures(line) {
+            let (addon, required) = (caps[1].to_string(), caps[2].to_string());
+            let provider = index.patch_provider(&required);
+            let needed_by = index.patch_provider(&addon);
+
+            (
+                IssueKind::RequiresAddon {
+                    addon,
+                    required,
+                    needed_by,
+                },
+                provider,
+            )
+        } else if let Some(caps) = no_entry.captures(line) {
+            let patch = caps[1].to_string();
+            let provider = index.patch_provider(&patch);
+
+            (IssueKind::NoPatch(patch), provider)
+        } else if file_error.is_match(line) {
+            let Some(caps) = file_path.captures(line) else {
+                continue;
+            };
+            let path = caps[1].to_string();
+
+            // Paths into the base game or unknown mods can't be fixed from here
+            let Some(provider) = index.path_provider(&path) else {
+                continue;
+            };
+
+            (IssueKind::NoFile(path), Some(provider))
+        } else {
+            continue;
+        };
+
+        // The same error is usually logged many times
+        if issues.iter().any(|issue| issue.kind == kind) {
+            continue;
+        }
+
+        issues.push(Issue { kind, provider });
+    }
+
+    issues
+}
@@ -1,6 +1,7 @@
 use std::{
     fs,
     io::{BufWriter, Write},
+    os::unix::fs::{OpenOptionsExt, PermissionsExt},
     path::{Path, PathBuf},
 };
 
@@ -20,6 +21,41 @@ where
     Ok(())
 }
 
+// Like write_json, but only readable by the owner, for files holding secrets
+pub fn write_json_private<T>(path: &Path, data: T) -> AppResult<()>
+where
+    T: Serialize,
+{
+    let file = create_private(path)?;
+    let mut writer = BufWriter::new(&file);
+    serde_json::to_writer_pretty(&mut writer, &data)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+// Like fs::write, but only readable by the owner, for files holding secrets
+pub fn write_private(path: &Path, contents: &str) -> AppResult<()> {
+    let mut file = create_private(path)?;
+    file.write_all(contents.as_bytes())?;
+
+    Ok(())
+}
+
+fn create_private(path: &Path) -> AppResult<fs::File> {
+    let file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+
+    // The file may have been created earlier with wider permissions
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+
+    Ok(file)
+}
+
 pub fn read_json<T>(path: &Path) -> AppResult<T>
 where
     T: DeserializeOwned,
@@ -0,0 +1,70 @@
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::AppResult;
+
+use super::{file_handler, utils};
+
+const HISTORY_FILE: &str = "arma3-mod-manager-cli-history.json";
+
+// The parts of the mod list an edit can change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub enabled_mods: Vec<u64>,
+    pub default_args: String,
+}
+
+// Undo and redo stacks of snapshots taken before each edit
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    undo: VecDeque<Snapshot>,
+    redo: Vec<Snapshot>,
+}
+
+impl History {
+    fn get_save_path() -> AppResult<PathBuf> {
+        let home_path = utils::get_home_path()?;
+
+        Ok(Path::new(&home_path).join(HISTORY_FILE))
+    }
+
+    // The history of the last session, or an empty one when there is none
+    pub fn read() -> History {
+        History::get_save_path()
+            .and_then(|path| file_handler::read_json(&path))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> AppResult<()> {
+        file_handler::write_json(&History::get_save_path()?, self)
+    }
+
+    // Remembers the state before an edit, dropping the oldest once over the limit
+    pub fn record(&mut self, before: Snapshot, limit: usize) {
+        self.undo.push_back(before);
+        self.redo.clear();
+
+        while self.undo.len() > limit {
+            self.undo.pop_front();
+        }
+    }
+
+    // The state to go back to, current is kept to redo
+    pub fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let previous = self.undo.pop_back()?;
+        self.redo.push(current);
+
+        Some(previous)
+    }
+
+    pub fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let next = self.redo.pop()?;
+        self.undo.push_back(current);
+
+        Some(next)
+    }
+}
@@ -0,0 +1,254 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::errors::{AppError, AppResult};
+
+use super::{
+    pbo::{self, Pbo},
+    vdf::VdfNode,
+};
+
+const WORKSHOP_MANIFEST: &str = "appworkshop_107410.acf";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PboProblem {
+    Empty,
+    InvalidHeader,
+    // Shorter than its header says
+    Truncated,
+    MissingChecksum,
+    ChecksumMismatch,
+}
+
+impl PboProblem {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PboProblem::Empty => "empty file",
+            PboProblem::InvalidHeader => "invalid header",
+            PboProblem::Truncated => "truncated",
+            PboProblem::MissingChecksum => "no checksum",
+            PboProblem::ChecksumMismatch => "checksum mismatch",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IntegrityReport {
+    pub size_on_disk: u64,
+    // Size Steam recorded for the item, if the manifest knows it
+    pub manifest_size: Option<u64>,
+    pub pbos_checked: usize,
+    pub problems: Vec<(String, PboProblem)>,
+}
+
+impl IntegrityReport {
+    pub fn size_matches(&self) -> bool {
+        self.manifest_size
+            .is_none_or(|size| size == self.size_on_disk)
+    }
+}
+
+// Compares the mod with Steam's manifest and validates every PBO it ships
+pub fn check_mod(workshop_path: &Path, id: u64) -> AppResult<IntegrityReport> {
+    let mod_path = workshop_path.join(id.to_string());
+
+    let manifest_size = read_manifest(workshop_path).ok().and_then(|(_, root)| {
+        root.get("WorkshopItemsInstalled")?
+            .get(&id.to_string())?
+            .get("size")?
+            .as_str()?
+            .parse()
+            .ok()
+    });
+
+    let mut problems = Vec::new();
+    let pbos = pbo::find_pbos(&mod_path);
+
+    for path in &pbos {
+        if let Some(problem) = check_pbo(path)? {
+            let name = path.file_name().unwrap_or_default();
+            problems.push((name.to_string_lossy().to_string(), problem));
+        }
+    }
+
+    Ok(IntegrityReport {
+        size_on_disk: dir_size(&mod_path)?,
+        manifest_size,
+        pbos_checked: pbos.len(),
+        problems,
+    })
+}
+
+fn check_pbo(path: &Path) -> AppResult<Option<PboProblem>> {
+    let len = fs::metadata(path)?.len();
+
+    if len == 0 {
+        return Ok(Some(PboProblem::Empty));
+    }
+
+    let Ok(pbo) = Pbo::read(path) else {
+        return Ok(Some(PboProblem::InvalidHeader));
+    };
+
+    if len < pbo.data_end {
+        return Ok(Some(PboProblem::Truncated));
+    }
+
+    let Some(stored) = pbo.stored_checksum()? else {
+        return Ok(Some(PboProblem::MissingChecksum));
+    };
+
+    if stored != pbo.computed_checksum()? {
+        return Ok(Some(PboProblem::ChecksumMismatch));
+    }
+
+    Ok(None)
+}
+
+fn dir_size(path: &Path) -> AppResult<u64> {
+    let mut size = 0;
+
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+
+        if metadata.is_dir() {
+            size += dir_size(&entry.path())?;
+        } else if metadata.is_file() {
+            size += metadata.len();
+        }
+    }
+
+    Ok(size)
+}
+
+// steamapps/workshop/appworkshop_107410.acf, two levels above the workshop content folder
+fn get_manifest_path(workshop_path: &Path) -> AppResult<PathBuf> {
+    workshop_path
+        .parent()
+        .and_then(|p| p.parent())
+        .map(|p| p.join(WORKSHOP_MANIFEST))
+        .ok_or_else(|| AppError::InvalidPath(workshop_path.to_string_lossy().into()))
+}
+
+fn read_manifest(workshop_path: &Path) -> AppResult<(String, VdfNode)> {
+    let path = get_manifest_path(workshop_path)?;
+    let text = fs::read_to_string(&path)
+        .map_err(|_| AppError::InvalidPath(path.to_string_lossy().into()))?;
+
+    VdfNode::parse(&text)
+}
+
+// Removes the item from Steam's manifest so Steam downloads it again
+//
+// Steam keeps the manifest in memory, so this only takes effect when Steam is started
+// afterwards. A backup of the manifest is written next to it first
+pub fn mark_for_reverification(workshop_path: &Path, id: u64) -> AppResult<()> {
+    let path = get_manifest_path(workshop_path)?;
+    let (key, mut root) = read_manifest(workshop_path)?;
+
+    let mut removed = false;
+    for section in ["WorkshopItemsInstalled", "WorkshopItemDetails"] {
+        if let Some(items) = root.get_mut(section) {
+            removed |= items.remove(&id.to_string());
+        }
+    }
+
+    if !removed {
+        return Err(AppError::InvalidPath(format!(
+            "{} in {}",
+            id,
+            path.to_string_lossy()
+        )));
+    }
+
+    fs::copy(&path, path.with_extension("acf.bak"))?;
+    fs::write(&path, root.to_vdf(&key))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use sha1::{Digest, Sha1};
+
+    use super::*;
+
+    const CONFIG: &[u8] = b"class CfgPatches { class test_addon {}; };";
+
+    // A PBO with a prefix and a single config, followed by its checksum
+    fn pbo_bytes() -> Vec<u8> {
+        let mut data = Vec::new();
+
+        // Version entry with its properties
+        data.push(0);
+        data.extend(0x5665_7273u32.to_le_bytes());
+        data.extend([0; 16]);
+        data.extend(b"prefix\0test\0\0");
+
+        data.extend(b"config.cpp\0");
+        data.extend(0u32.to_le_bytes());
+        data.extend((CONFIG.len() as u32).to_le_bytes());
+        data.extend([0; 8]);
+        data.extend((CONFIG.len() as u32).to_le_bytes());
+
+        // Terminating entry
+        data.extend([0; 21]);
+
+        data.extend(CONFIG);
+
+        let checksum: [u8; 20] = Sha1::digest(&data).into();
+        data.push(0);
+        data.extend(checksum);
+
+        data
+    }
+
+    // Writes the bytes to a file of its own in the temp dir, as check_pbo reads from disk
+    fn check(name: &str, data: &[u8]) -> Option<PboProblem> {
+        let dir = std::env::temp_dir().join(format!("a3mm-integrity-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join(format!("{}.pbo", name));
+        fs::write(&path, data).unwrap();
+
+        let problem = check_pbo(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        problem
+    }
+
+    #[test]
+    fn accepts_an_intact_pbo() {
+        assert_eq!(check("intact", &pbo_bytes()), None);
+    }
+
+    #[test]
+    fn reports_damaged_pbos() {
+        let data = pbo_bytes();
+        let data_end = data.len() - 21;
+
+        assert_eq!(check("empty", &[]), Some(PboProblem::Empty));
+        assert_eq!(
+            check("header", &data[..20]),
+            Some(PboProblem::InvalidHeader)
+        );
+        assert_eq!(
+            check("truncated", &data[..data_end - 5]),
+            Some(PboProblem::Truncated)
+        );
+        assert_eq!(
+            check("unsigned", &data[..data_end]),
+            Some(PboProblem::MissingChecksum)
+        );
+
+        let mut modified = data.clone();
+        modified[data_end - 1] ^= 0xff;
+        assert_eq!(
+            check("modified", &modified),
+            Some(PboProblem::ChecksumMismatch)
+        );
+    }
+}
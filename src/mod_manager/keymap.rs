@@ -0,0 +1,496 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{AppError, AppResult};
+
+// Everything the keys of the mod list can do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Top,
+    Bottom,
+    JumpToLetter,
+    Toggle,
+    ToggleAll,
+    Mark,
+    Visual,
+    Enable,
+    Disable,
+    Invert,
+    Tag,
+    EnableMatching,
+    DisableMatching,
+    Details,
+    Save,
+    Undo,
+    Redo,
+    Refresh,
+    Parameters,
+    Launch,
+    StopGame,
+    GameLog,
+    ServerProfiles,
+    SavedServers,
+    CheckSignatures,
+    VerifySignatures,
+    Back,
+}
+
+impl Action {
+    const ALL: [Action; 31] = [
+        Action::Up,
+        Action::Down,
+        Action::PageUp,
+        Action::PageDown,
+        Action::Top,
+        Action::Bottom,
+        Action::JumpToLetter,
+        Action::Toggle,
+        Action::ToggleAll,
+        Action::Mark,
+        Action::Visual,
+        Action::Enable,
+        Action::Disable,
+        Action::Invert,
+        Action::Tag,
+        Action::EnableMatching,
+        Action::DisableMatching,
+        Action::Details,
+        Action::Save,
+        Action::Undo,
+        Action::Redo,
+        Action::Refresh,
+        Action::Parameters,
+        Action::Launch,
+        Action::StopGame,
+        Action::GameLog,
+        Action::ServerProfiles,
+        Action::SavedServers,
+        Action::CheckSignatures,
+        Action::VerifySignatures,
+        Action::Back,
+    ];
+
+    // The name used for the action in the config
+    fn name(self) -> &'static str {
+        match self {
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::PageUp => "page_up",
+            Action::PageDown => "page_down",
+            Action::Top => "top",
+            Action::Bottom => "bottom",
+            Action::JumpToLetter => "jump_to_letter",
+            Action::Toggle => "toggle",
+            Action::ToggleAll => "toggle_all",
+            Action::Mark => "mark",
+            Action::Visual => "visual",
+            Action::Enable => "enable",
+            Action::Disable => "disable",
+            Action::Invert => "invert",
+            Action::Tag => "tag",
+            Action::EnableMatching => "enable_matching",
+            Action::DisableMatching => "disable_matching",
+            Action::Details => "details",
+            Action::Save => "save",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::Refresh => "refresh",
+            Action::Parameters => "parameters",
+            Action::Launch => "launch",
+            Action::StopGame => "stop_game",
+            Action::GameLog => "game_log",
+            Action::ServerProfiles => "server_profiles",
+            Action::SavedServers => "saved_servers",
+            Action::CheckSignatures => "check_signatures",
+            Action::VerifySignatures => "verify_signatures",
+            Action::Back => "back",
+        }
+    }
+
+    fn default_chords(self) -> &'static [&'static str] {
+        match self {
+            Action::Up => &["w", "k", "up"],
+            Action::Down => &["s", "j", "down"],
+            Action::PageUp => &["a", "h", "left", "pageup"],
+            Action::PageDown => &["d", "l", "right", "pagedown"],
+            Action::Top => &["g g", "home"],
+            Action::Bottom => &["G", "end"],
+            Action::JumpToLetter => &["'"],
+            Action::Toggle => &["space"],
+            Action::ToggleAll => &["ctrl+space"],
+            Action::Mark => &["m"],
+            Action::Visual => &["V"],
+            Action::Enable => &["+"],
+            Action::Disable => &["-"],
+            Action::Invert => &["*"],
+            Action::Tag => &["t"],
+            Action::EnableMatching => &["E"],
+            Action::DisableMatching => &["D"],
+            Action::Details => &["enter"],
+            Action::Save => &["ctrl+s"],
+            Action::Undo => &["u"],
+            Action::Redo => &["ctrl+r"],
+            Action::Refresh => &["r"],
+            Action::Parameters => &["f"],
+            Action::Launch => &["p"],
+            Action::StopGame => &["x"],
+            Action::GameLog => &["v"],
+            Action::ServerProfiles => &["o"],
+            Action::SavedServers => &["b"],
+            Action::CheckSignatures => &["c"],
+            Action::VerifySignatures => &["C"],
+            Action::Back => &["esc"],
+        }
+    }
+}
+
+// The help panel, in order, with the actions each line lists the keys of
+const HELP: [(&str, &[Action]); 24] = [
+    ("Move Up / Down", &[Action::Up, Action::Down]),
+    ("Page Up", &[Action::PageUp]),
+    ("Page Down", &[Action::PageDown]),
+    ("Jump To Top / Bottom", &[Action::Top, Action::Bottom]),
+    ("Jump To Letter", &[Action::JumpToLetter]),
+    ("Toggle Selected Mod", &[Action::Toggle]),
+    ("Toggle All Mods", &[Action::ToggleAll]),
+    ("Mark / Select Range", &[Action::Mark, Action::Visual]),
+    (
+        "Enable / Disable Marked",
+        &[Action::Enable, Action::Disable],
+    ),
+    ("Invert Marked", &[Action::Invert]),
+    ("Tag Marked", &[Action::Tag]),
+    (
+        "Enable / Disable Regex",
+        &[Action::EnableMatching, Action::DisableMatching],
+    ),
+    ("Mod Details", &[Action::Details]),
+    ("Save Enabled Mods", &[Action::Save]),
+    ("Undo / Redo", &[Action::Undo, Action::Redo]),
+    ("Refresh Mods", &[Action::Refresh]),
+    ("Set Custom Parameters", &[Action::Parameters]),
+    ("Launch Game", &[Action::Launch]),
+    ("Stop Game", &[Action::StopGame]),
+    ("View Game Log", &[Action::GameLog]),
+    ("Server Profiles", &[Action::ServerProfiles]),
+    ("Saved Servers", &[Action::SavedServers]),
+    (
+        "Check / Verify Signatures",
+        &[Action::CheckSignatures, Action::VerifySignatures],
+    ),
+    ("Back / Quit", &[Action::Back]),
+];
+
+// A key with its modifiers, shift is part of the character for character keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyPress {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyPress {
+    pub fn from_event(event: &KeyEvent) -> KeyPress {
+        let mut modifiers =
+            event.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+
+        if let KeyCode::Char(_) = event.code {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+
+        KeyPress {
+            code: event.code,
+            modifiers,
+        }
+    }
+
+    pub fn char(&self) -> Option<char> {
+        match self.code {
+            KeyCode::Char(c) if self.modifiers.is_empty() => Some(c),
+            _ => None,
+        }
+    }
+
+    // Parses keys written like ctrl+s, shift+c, C, space or pagedown
+    fn parse(text: &str) -> AppResult<KeyPress> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = text;
+
+        // The key itself may be +, so only split off known modifiers
+        while let Some((modifier, key)) = rest.split_once('+') {
+            let modifier = match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => break,
+            };
+
+            if key.is_empty() {
+                break;
+            }
+
+            modifiers |= modifier;
+            rest = key;
+        }
+
+        let mut chars = rest.chars();
+
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => {
+                if modifiers.contains(KeyModifiers::SHIFT) {
+                    modifiers.remove(KeyModifiers::SHIFT);
+                    KeyCode::Char(c.to_ascii_uppercase())
+                } else {
+                    KeyCode::Char(c)
+                }
+            }
+            _ => match rest.to_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(AppError::InvalidKey(text.to_string())),
+                },
+            },
+        };
+
+        Ok(KeyPress { code, modifiers })
+    }
+
+    fn label(&self) -> String {
+        let key = match self.code {
+            KeyCode::Char(' ') => "<SPACE>".to_string(),
+            KeyCode::Char(c) if c.is_ascii_uppercase() => format!("<SHIFT> + {}", c),
+            KeyCode::Char(c) => c.to_uppercase().to_string(),
+            KeyCode::Enter => "<ENTER>".to_string(),
+            KeyCode::Esc => "<ESC>".to_string(),
+            KeyCode::Up => "<UP>".to_string(),
+            KeyCode::Down => "<DOWN>".to_string(),
+            KeyCode::Left => "<LEFT>".to_string(),
+            KeyCode::Right => "<RIGHT>".to_string(),
+            KeyCode::PageUp => "<PGUP>".to_string(),
+            KeyCode::PageDown => "<PGDN>".to_string(),
+            KeyCode::Home => "<HOME>".to_string(),
+            KeyCode::End => "<END>".to_string(),
+            KeyCode::Tab => "<TAB>".to_string(),
+            KeyCode::Backspace => "<BACKSPACE>".to_string(),
+            KeyCode::Delete => "<DEL>".to_string(),
+            KeyCode::Insert => "<INS>".to_string(),
+            KeyCode::F(n) => format!("<F{}>", n),
+            code => format!("{:?}", code),
+        };
+
+        let mut label = String::new();
+
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label += "<CTRL> + ";
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label += "<ALT> + ";
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            label += "<SHIFT> + ";
+        }
+
+        label + &key
+    }
+}
+
+// One or more keys pressed after each other, like g g
+type Chord = Vec<KeyPress>;
+
+fn chord_label(chord: &Chord) -> String {
+    // Sequences of plain characters are shown as typed, like gg
+    let typed = chord
+        .iter()
+        .map(|key| key.char())
+        .collect::<Option<String>>();
+
+    match typed {
+        Some(typed) if chord.len() > 1 => typed,
+        _ => chord
+            .iter()
+            .map(|key| key.label())
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+// What the keys typed so far amount to
+pub enum Lookup {
+    Action(Action),
+    // The start of a longer chord
+    Prefix,
+    Unbound,
+}
+
+#[derive(Debug)]
+pub struct Keymap {
+    // Each chord with its action and how it was written in the config
+    chords: Vec<(Chord, Action, String)>,
+}
+
+impl Keymap {
+    // The default keys with the ones set in the config replacing them per action
+    pub fn new(overrides: &BTreeMap<Action, Vec<String>>) -> AppResult<Keymap> {
+        let mut chords = Vec::new();
+
+        for action in Action::ALL {
+            let texts = match overrides.get(&action) {
+                Some(texts) => texts.iter().map(String::as_str).collect(),
+                None => action.default_chords().to_vec(),
+            };
+
+            for text in texts {
+                let chord = text
+                    .split_whitespace()
+                    .map(KeyPress::parse)
+                    .collect::<AppResult<Chord>>()?;
+
+                if chord.is_empty() {
+                    return Err(AppError::InvalidKey(text.to_string()));
+                }
+
+                chords.push((chord, action, text.to_string()));
+            }
+        }
+
+        let keymap = Keymap { chords };
+        keymap.validate()?;
+
+        Ok(keymap)
+    }
+
+    // A key may only do one thing, so it can't be bound twice or start a longer chord
+    fn validate(&self) -> AppResult<()> {
+        let mut seen: HashMap<&Chord, Action> = HashMap::new();
+
+        for (chord, action, text) in &self.chords {
+            // Digits are counts, like the 5 in 5j
+            if chord[0].char().is_some_and(|c| c.is_ascii_digit()) {
+                return Err(AppError::KeymapConflict(format!(
+                    "'{}' for {} is used for counts",
+                    text,
+                    action.name()
+                )));
+            }
+
+            if let Some(other) = seen.insert(chord, *action) {
+                if other != *action {
+                    return Err(AppError::KeymapConflict(format!(
+                        "'{}' is bound to both {} and {}",
+                        text,
+                        other.name(),
+                        action.name()
+                    )));
+                }
+            }
+        }
+
+        for (chord, action, text) in &self.chords {
+            let longer = self
+                .chords
+                .iter()
+                .find(|(other, _, _)| other.len() > chord.len() && other.starts_with(chord));
+
+            if let Some((_, other_action, other_text)) = longer {
+                return Err(AppError::KeymapConflict(format!(
+                    "'{}' for {} is the start of '{}' for {}",
+                    text,
+                    action.name(),
+                    other_text,
+                    other_action.name()
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn lookup(&self, keys: &[KeyPress]) -> Lookup {
+        let mut prefix = false;
+
+        for (chord, action, _) in &self.chords {
+            if chord.as_slice() == keys {
+                return Lookup::Action(*action);
+            }
+
+            prefix |= chord.starts_with(keys);
+        }
+
+        if prefix {
+            Lookup::Prefix
+        } else {
+            Lookup::Unbound
+        }
+    }
+
+    fn label(&self, action: Action) -> String {
+        let labels = self
+            .chords
+            .iter()
+            .filter(|(_, a, _)| *a == action)
+            .map(|(chord, _, _)| chord_label(chord))
+            .collect::<Vec<_>>();
+
+        if labels.is_empty() {
+            return "-".to_string();
+        }
+
+        let label = labels.join(", ");
+
+        match action {
+            Action::JumpToLetter => label + " + <LETTER>",
+            _ => label,
+        }
+    }
+
+    pub fn help(&self) -> Vec<HelpEntry> {
+        HELP.iter()
+            .map(|(text, actions)| HelpEntry {
+                text,
+                keys: actions
+                    .iter()
+                    .map(|action| (*action, self.label(*action)))
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+// A line of the help panel, with the keys of each action it lists
+pub struct HelpEntry {
+    pub text: &'static str,
+    pub keys: Vec<(Action, String)>,
+}
+
+impl HelpEntry {
+    pub const KEY_SEPARATOR: &'static str = " / ";
+
+    pub fn keybinding(&self) -> String {
+        self.keys
+            .iter()
+            .map(|(_, label)| label.as_str())
+            .collect::<Vec<_>>()
+            .join(Self::KEY_SEPARATOR)
+    }
+}
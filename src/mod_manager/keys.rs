@@ -0,0 +1,145 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::AppResult;
+
+use super::{deploy::DeployStrategy, file_handler};
+
+const MANIFEST_FILE: &str = ".arma3-mod-manager-cli-keys.json";
+
+// Keys the manager has placed in a keys folder, so only those are ever removed
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    keys: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct KeyReport {
+    pub collected: usize,
+    pub removed: usize,
+    // Mods without any .bikey
+    pub missing: Vec<u64>,
+}
+
+// Finds the .bikey files in the mod's keys/ or key/ folder, whatever its case
+pub fn find_keys(mod_path: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(mod_path) else {
+        return Vec::new();
+    };
+
+    let mut keys = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_dir()
+                && p.file_name().is_some_and(|n| {
+                    n.eq_ignore_ascii_case("keys") || n.eq_ignore_ascii_case("key")
+                })
+        })
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("bikey"))
+        })
+        .collect::<Vec<_>>();
+
+    keys.sort();
+    keys
+}
+
+// Places the keys of the given mods into keys_dir and removes the ones placed
+// earlier for mods that are no longer in the list
+//
+// Keys that were already in keys_dir before the manager touched it are left alone
+pub fn sync_keys(
+    keys_dir: &Path,
+    mods: &[(u64, PathBuf)],
+    strategy: DeployStrategy,
+) -> AppResult<KeyReport> {
+    fs::create_dir_all(keys_dir)?;
+
+    let manifest_path = keys_dir.join(MANIFEST_FILE);
+    let old_manifest: Manifest = file_handler::read_json(&manifest_path).unwrap_or_default();
+
+    let mut report = KeyReport::default();
+    let mut manifest = Manifest::default();
+
+    for (id, mod_path) in mods {
+        let keys = find_keys(mod_path);
+
+        if keys.is_empty() {
+            report.missing.push(*id);
+            continue;
+        }
+
+        for key in keys {
+            let Some(name) = key.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+
+            // Several mods often ship the same key
+            if manifest.keys.iter().any(|k| k == name) {
+                continue;
+            }
+
+            let target = keys_dir.join(name);
+            let placed_by_us = old_manifest.keys.iter().any(|k| k == name);
+
+            if target.symlink_metadata().is_ok() {
+                if !placed_by_us {
+                    continue;
+                }
+                fs::remove_file(&target)?;
+            }
+
+            match strategy {
+                DeployStrategy::Symlink => std::os::unix::fs::symlink(&key, &target)?,
+                // The keys folder may well be on another filesystem than the workshop
+                DeployStrategy::Hardlink => {
+                    if fs::hard_link(&key, &target).is_err() {
+                        fs::copy(&key, &target)?;
+                    }
+                }
+                DeployStrategy::Copy => {
+                    fs::copy(&key, &target)?;
+                }
+            }
+
+            manifest.keys.push(name.to_string());
+            report.collected += 1;
+        }
+    }
+
+    // A key that can't be removed stays in the manifest, so the next sync tries again
+    let mut failed = None;
+
+    for name in &old_manifest.keys {
+        if manifest.keys.contains(name) {
+            continue;
+        }
+
+        match fs::remove_file(keys_dir.join(name)) {
+            Ok(()) => report.removed += 1,
+            // Already removed by hand
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => {
+                manifest.keys.push(name.clone());
+                failed.get_or_insert(e);
+            }
+        }
+    }
+
+    file_handler::write_json(&manifest_path, &manifest)?;
+
+    match failed {
+        Some(e) => Err(e.into()),
+        None => Ok(report),
+    }
+}
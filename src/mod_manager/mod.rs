@@ -1,33 +1,64 @@
 use std::{
-    fs,
+    collections::HashSet,
     path::{Path, PathBuf},
 };
 
-use regex::Regex;
-
 use crate::errors::{AppError, AppResult};
 
-use self::{config::Config, paginator::Paginator, terminal::Terminal};
+use self::{
+    config::Config, history::Snapshot, paginator::Paginator, signatures::SignatureStatus,
+    terminal::Terminal,
+};
 
+mod a2s;
 mod config;
+mod connection;
+mod deploy;
+mod diagnostics;
 mod file_handler;
+mod history;
+mod integrity;
+mod keymap;
+mod keys;
+mod mod_index;
 mod paginator;
+mod pbo;
+mod process;
+mod rpt;
+mod server;
+mod server_cfg;
+mod signatures;
 mod terminal;
+mod theme;
 mod utils;
+mod vdf;
+mod watcher;
+
+// How a mod changed in the workshop folder while the manager was running
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModChange {
+    New,
+    Updated,
+}
 
 #[derive(Debug, Clone)]
 pub struct Mod {
     pub id: u64,
     pub name: String,
     pub enabled: bool,
+    // Not known until the signatures have been checked
+    pub signature: Option<SignatureStatus>,
+    pub change: Option<ModChange>,
 }
 
 impl Mod {
     fn new(id: u64, name: String) -> Mod {
         Mod {
             id,
-            name,
+            name: utils::sanitize_name(&name),
             enabled: false,
+            signature: None,
+            change: None,
         }
     }
 
@@ -36,6 +67,9 @@ impl Mod {
     }
 }
 
+// Until the terminal sizes the pages to its height
+const PAGE_SIZE: usize = 15;
+
 #[derive(Debug)]
 pub struct ModManager {
     config: Config,
@@ -43,7 +77,7 @@ pub struct ModManager {
 }
 
 impl ModManager {
-    pub fn new(page_size: usize) -> AppResult<Self> {
+    pub fn new() -> AppResult<Self> {
         match Config::read() {
             Ok(config) => {
                 let mut loaded_mods = ModManager::get_installed_mods(config.get_workshop_path())?;
@@ -59,10 +93,13 @@ impl ModManager {
                     }
                 }
 
-                Ok(ModManager {
+                let mut mod_manager = ModManager {
                     config,
-                    loaded_mods: Paginator::new(loaded_mods, page_size),
-                })
+                    loaded_mods: Paginator::new(loaded_mods, PAGE_SIZE),
+                };
+                mod_manager.check_signatures(false);
+
+                Ok(mod_manager)
             }
 
             Err(AppError::IoError(io_error)) if io_error.kind() == std::io::ErrorKind::NotFound => {
@@ -74,7 +111,7 @@ impl ModManager {
 
                 Ok(ModManager {
                     config,
-                    loaded_mods: Paginator::new(loaded_mods, page_size),
+                    loaded_mods: Paginator::new(loaded_mods, PAGE_SIZE),
                 })
             }
             Err(e) => Err(e),
@@ -82,82 +119,158 @@ impl ModManager {
     }
 
     pub fn start(&mut self) -> AppResult<()> {
-        let mut term = Terminal::new(self);
+        let mut term = Terminal::new(self)?;
 
         term.run()?;
 
         Ok(())
     }
 
+    // Rescans the workshop folder, keeping which mods are enabled
     pub fn refresh_mods(&mut self) -> AppResult<()> {
-        let installed_mods = ModManager::get_installed_mods(self.config.get_workshop_path())?;
-        self.loaded_mods = Paginator::new(installed_mods, self.loaded_mods.page_size);
+        let mut installed_mods = ModManager::get_installed_mods(self.config.get_workshop_path())?;
+
+        for m in &mut installed_mods {
+            m.enabled = self.find_mod(m.id).is_some_and(|old| old.enabled);
+        }
+
+        self.loaded_mods.set_items(installed_mods);
+        self.check_signatures(false);
 
         Ok(())
     }
 
-    fn get_installed_mods(workshop_path: &Path) -> AppResult<Vec<Mod>> {
-        let mut mods: Vec<Mod> = Vec::new();
+    pub fn enabled_mod_ids(&self) -> Vec<u64> {
+        self.loaded_mods
+            .filter(|m| m.enabled)
+            .iter()
+            .map(|m| m.id)
+            .collect()
+    }
 
-        match fs::read_dir(&workshop_path) {
-            Ok(installed_mods) => {
-                for entry in installed_mods {
-                    let entry = match entry {
-                        Ok(e) => e,
-                        Err(_) => continue,
-                    };
+    // Whether the enabled mods differ from the ones saved in the config
+    //
+    // Saved mods that aren't installed right now don't count
+    pub fn has_unsaved_changes(&self) -> bool {
+        let saved = self.config.get_enabled_mods();
 
-                    let path = entry.path();
+        self.loaded_mods
+            .all_items()
+            .iter()
+            .any(|m| m.enabled != saved.contains(&m.id))
+    }
 
-                    if !path.is_dir() {
-                        continue;
-                    }
+    // Saves the enabled mods, keeping the saved ones that aren't installed right now
+    pub fn save_enabled_mods(&mut self) -> AppResult<()> {
+        let mut mods = self.enabled_mod_ids();
 
-                    let mod_id: u64 = match path
-                        .file_name()
-                        .and_then(|name| name.to_str())
-                        .and_then(|s| s.parse().ok())
-                    {
-                        Some(id) => id,
-                        None => continue,
-                    };
-
-                    // If the meta.cpp file is not present, skip
-                    let mod_path = Path::new(&path).join("meta.cpp");
-                    if !mod_path.exists() {
-                        continue;
-                    }
+        mods.extend(
+            self.config
+                .get_enabled_mods()
+                .into_iter()
+                .filter(|id| self.find_mod(*id).is_none()),
+        );
 
-                    let mod_content =
-                        fs::read(&mod_path).map_err(|_| AppError::MissingMeta(mod_id))?;
+        self.config.update_mods(mods);
+        self.config.save()
+    }
 
-                    let content_str = String::from_utf8_lossy(&mod_content);
+    // The enabled mods and launch parameters, to undo an edit of them
+    pub fn snapshot(&self) -> Snapshot {
+        // Sorted, so reordering the list isn't taken for an edit
+        let mut enabled_mods = self.enabled_mod_ids();
+        enabled_mods.sort_unstable();
 
-                    let mut name = match Regex::new(r#"name\s*=\s*"([^"]+)""#)
-                        .unwrap()
-                        .captures(&content_str)
-                        .and_then(|caps| caps.get(1))
-                        .map(|m| m.as_str().to_string())
-                    {
-                        Some(name) => name,
-                        None => continue,
-                    };
-
-                    // Uppercase the first letter of the name
-                    let mut chars = name.chars();
-                    name = match chars.next() {
-                        None => String::new(),
-                        Some(f) => f.to_uppercase().collect::<String>() + chars.as_str(),
-                    };
-
-                    mods.push(Mod::new(mod_id, name.to_string()));
+        Snapshot {
+            enabled_mods,
+            default_args: self.config.get_default_args().to_string(),
+        }
+    }
+
+    // Puts back a snapshot, mods that are no longer installed are skipped
+    pub fn restore(&mut self, snapshot: &Snapshot) -> AppResult<()> {
+        for m in self.loaded_mods.all_items_mut() {
+            m.enabled = snapshot.enabled_mods.contains(&m.id);
+        }
+
+        // The parameters are saved as soon as they are edited, so undoing them is saved too
+        if snapshot.default_args != self.config.get_default_args() {
+            self.config.set_default_args(snapshot.default_args.clone());
+            self.config.save()?;
+        }
+
+        Ok(())
+    }
+
+    // Rescans the workshop folder after the given mods changed on disk
+    //
+    // Unlike refresh_mods this keeps the state of the mods that are still there, and marks
+    // the new and changed ones. Returns the names of the mods that were removed
+    pub fn apply_changes(&mut self, changed: &HashSet<u64>) -> AppResult<Vec<String>> {
+        let mut installed_mods = ModManager::get_installed_mods(self.config.get_workshop_path())?;
+
+        for m in &mut installed_mods {
+            match self.find_mod(m.id) {
+                Some(old) => {
+                    m.enabled = old.enabled;
+                    m.signature = old.signature.clone();
+                    m.change = old.change;
+
+                    if changed.contains(&m.id) {
+                        m.change = m.change.or(Some(ModChange::Updated));
+                        m.signature = None;
+                    }
                 }
+                None => m.change = Some(ModChange::New),
             }
-            Err(e) => println!("{}\n{:?}", e, workshop_path),
         }
 
-        mods.sort_by(|a, b| a.name.cmp(&b.name));
+        let removed = self
+            .loaded_mods
+            .all_items()
+            .iter()
+            .filter(|old| !installed_mods.iter().any(|m| m.id == old.id))
+            .map(|old| old.name.clone())
+            .collect();
+
+        self.loaded_mods.set_items(installed_mods);
+
+        // Updated mods ship new signatures
+        let workshop_path = self.config.get_workshop_path().to_path_buf();
+        for m in self.loaded_mods.all_items_mut() {
+            if m.enabled && m.signature.is_none() {
+                m.signature = Some(signatures::check_mod(&m.get_path(&workshop_path), false));
+            }
+        }
+
+        Ok(removed)
+    }
 
-        Ok(mods)
+    // Checks the .bisign files of the enabled mods against the keys they ship
+    //
+    // With deep set the RSA signatures are verified too, which reads every PBO
+    pub fn check_signatures(&mut self, deep: bool) {
+        let workshop_path = self.config.get_workshop_path().to_path_buf();
+
+        for m in self.loaded_mods.all_items_mut() {
+            if m.enabled {
+                m.signature = Some(signatures::check_mod(&m.get_path(&workshop_path), deep));
+            }
+        }
+    }
+
+    pub fn find_mod(&self, id: u64) -> Option<&Mod> {
+        self.loaded_mods.all_items().iter().find(|m| m.id == id)
+    }
+
+    pub fn find_mod_mut(&mut self, id: u64) -> Option<&mut Mod> {
+        self.loaded_mods
+            .all_items_mut()
+            .iter_mut()
+            .find(|m| m.id == id)
+    }
+
+    fn get_installed_mods(workshop_path: &Path) -> AppResult<Vec<Mod>> {
+        mod_index::scan(workshop_path)
     }
 }
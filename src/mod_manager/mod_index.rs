@@ -0,0 +1,216 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{AppError, AppResult};
+
+use super::{file_handler, utils, Mod};
+
+const INDEX_FILE: &str = "arma3-mod-manager-cli-index.json";
+
+// What was read from a mod folder, valid as long as the folder and its meta.cpp are unchanged
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct IndexEntry {
+    id: u64,
+    mtime: u128,
+    meta_mtime: u128,
+    // None for folders that aren't a mod, so they aren't read again either
+    name: Option<String>,
+}
+
+// Persistent index of the workshop folder by mod folder path
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ModIndex {
+    entries: HashMap<String, IndexEntry>,
+}
+
+impl ModIndex {
+    fn get_save_path() -> AppResult<PathBuf> {
+        let home_path = utils::get_home_path()?;
+
+        Ok(Path::new(&home_path).join(INDEX_FILE))
+    }
+
+    fn read() -> ModIndex {
+        ModIndex::get_save_path()
+            .and_then(|path| file_handler::read_json(&path))
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> AppResult<()> {
+        file_handler::write_json(&ModIndex::get_save_path()?, self)
+    }
+}
+
+// A mod folder with the modification times its index entry is keyed by
+struct Folder {
+    path: PathBuf,
+    id: u64,
+    mtime: u128,
+    meta_mtime: u128,
+}
+
+// Lists the installed mods, only reading the folders that changed since the last scan
+pub fn scan(workshop_path: &Path) -> AppResult<Vec<Mod>> {
+    let mut index = ModIndex::read();
+
+    let folders = fs::read_dir(workshop_path)
+        .map_err(|e| {
+            AppError::WorkshopUnreadable(workshop_path.to_string_lossy().to_string(), e.to_string())
+        })?
+        .filter_map(|e| e.ok())
+        .filter_map(|e| read_folder(e.path()))
+        .collect::<Vec<_>>();
+
+    let (fresh, changed): (Vec<_>, Vec<_>) = folders.into_iter().partition(|folder| {
+        index
+            .entries
+            .get(&folder.path.to_string_lossy().to_string())
+            .is_some_and(|entry| {
+                entry.id == folder.id
+                    && entry.mtime == folder.mtime
+                    && entry.meta_mtime == folder.meta_mtime
+            })
+    });
+
+    let mut entries = fresh
+        .iter()
+        .filter_map(|folder| {
+            let key = folder.path.to_string_lossy().to_string();
+            index.entries.remove_entry(&key)
+        })
+        .collect::<HashMap<_, _>>();
+
+    let index_changed = !changed.is_empty() || !index.entries.is_empty();
+
+    for (folder, entry) in changed.iter().zip(read_changed(&changed)?) {
+        entries.insert(folder.path.to_string_lossy().to_string(), entry);
+    }
+
+    let mut mods = entries
+        .values()
+        .filter_map(|entry| {
+            entry
+                .name
+                .as_ref()
+                .map(|name| Mod::new(entry.id, name.clone()))
+        })
+        .collect::<Vec<_>>();
+
+    mods.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // Entries left in the old index belong to folders that are gone
+    if index_changed {
+        index.entries = entries;
+        index.save()?;
+    }
+
+    Ok(mods)
+}
+
+fn read_folder(path: PathBuf) -> Option<Folder> {
+    let metadata = fs::metadata(&path).ok()?;
+
+    if !metadata.is_dir() {
+        return None;
+    }
+
+    let id = path.file_name()?.to_str()?.parse().ok()?;
+
+    let meta_mtime = fs::metadata(path.join("meta.cpp"))
+        .map(|m| mtime(m.modified().ok()))
+        .unwrap_or_default();
+
+    Some(Folder {
+        mtime: mtime(metadata.modified().ok()),
+        meta_mtime,
+        path,
+        id,
+    })
+}
+
+fn mtime(time: Option<SystemTime>) -> u128 {
+    time.and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .unwrap_or_default()
+}
+
+// Reads the changed folders spread over worker threads, keeping their order
+fn read_changed(folders: &[Folder]) -> AppResult<Vec<IndexEntry>> {
+    if folders.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let name_regex = Regex::new(r#"name\s*=\s*"([^"]+)""#).unwrap();
+
+    let workers = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    let chunk_size = folders.len().div_ceil(workers);
+
+    thread::scope(|scope| {
+        let handles = folders
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let name_regex = &name_regex;
+
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|folder| read_entry(folder, name_regex))
+                        .collect::<AppResult<Vec<_>>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut entries = Vec::with_capacity(folders.len());
+        for handle in handles {
+            entries.extend(handle.join().expect("mod scan thread panicked")?);
+        }
+
+        Ok(entries)
+    })
+}
+
+fn read_entry(folder: &Folder, name_regex: &Regex) -> AppResult<IndexEntry> {
+    let mut entry = IndexEntry {
+        id: folder.id,
+        mtime: folder.mtime,
+        meta_mtime: folder.meta_mtime,
+        name: None,
+    };
+
+    // If the meta.cpp file is not present, skip
+    let meta_path = folder.path.join("meta.cpp");
+    if !meta_path.exists() {
+        return Ok(entry);
+    }
+
+    let mod_content = fs::read(&meta_path).map_err(|_| AppError::MissingMeta(folder.id))?;
+
+    let content_str = String::from_utf8_lossy(&mod_content);
+
+    let Some(name) = name_regex
+        .captures(&content_str)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_string())
+    else {
+        return Ok(entry);
+    };
+
+    // Uppercase the first letter of the name
+    let mut chars = name.chars();
+    entry.name = Some(match chars.next() {
+        None => String::new(),
+        Some(f) => f.to_uppercase().collect::<String>() + chars.as_str(),
+    });
+
+    Ok(entry)
+}
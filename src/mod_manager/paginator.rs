@@ -1,8 +1,14 @@
+// A list with a cursor, showing page_size items around it
 #[derive(Debug)]
 pub struct Paginator<T> {
     items: Vec<T>,
     pub page_size: usize,
-    pub current_page: usize,
+    // Index of the selected item
+    cursor: usize,
+    // Index of the first visible item
+    offset: usize,
+    // Flip whole pages instead of scrolling along with the cursor
+    pub paged: bool,
 }
 
 impl<T> Paginator<T> {
@@ -10,12 +16,29 @@ impl<T> Paginator<T> {
         Paginator {
             items,
             page_size,
-            current_page: 0,
+            cursor: 0,
+            offset: 0,
+            paged: false,
         }
     }
 
+    // Replaces the items, keeping the cursor where it was if it still exists
+    pub fn set_items(&mut self, items: Vec<T>) {
+        self.items = items;
+        self.set_cursor(self.cursor);
+    }
+
+    pub fn set_page_size(&mut self, page_size: usize) {
+        self.page_size = page_size;
+        self.set_cursor(self.cursor);
+    }
+
     pub fn total_pages(&self) -> usize {
-        (self.items.len() + self.page_size - 1) / self.page_size
+        self.items.len().div_ceil(self.page_size)
+    }
+
+    pub fn current_page(&self) -> usize {
+        self.offset / self.page_size
     }
 
     pub fn all_items(&self) -> &[T] {
@@ -27,20 +50,67 @@ impl<T> Paginator<T> {
     }
 
     pub fn current_page_items(&self) -> &[T] {
-        let start = self.current_page * self.page_size;
+        let start = usize::min(self.offset, self.items.len());
         let end = usize::min(start + self.page_size, self.items.len());
         &self.items[start..end]
     }
 
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn selected(&self) -> Option<&T> {
+        self.items.get(self.cursor)
+    }
+
+    pub fn selected_mut(&mut self) -> Option<&mut T> {
+        self.items.get_mut(self.cursor)
+    }
+
+    // Moves the cursor to the item, or as close to it as the list goes, and scrolls it into view
+    pub fn set_cursor(&mut self, index: usize) {
+        self.cursor = index.min(self.items.len().saturating_sub(1));
+
+        if self.paged {
+            self.offset = self.cursor - self.cursor % self.page_size;
+            return;
+        }
+
+        if self.cursor < self.offset {
+            self.offset = self.cursor;
+        } else if self.cursor >= self.offset + self.page_size {
+            self.offset = self.cursor + 1 - self.page_size;
+        }
+
+        // Don't leave empty rows at the bottom while there are items above
+        self.offset = self
+            .offset
+            .min(self.items.len().saturating_sub(self.page_size));
+    }
+
+    pub fn move_cursor(&mut self, delta: isize) {
+        self.set_cursor(self.cursor.saturating_add_signed(delta));
+    }
+
     pub fn next_page(&mut self) {
-        if self.current_page + 1 < self.total_pages() {
-            self.current_page += 1;
+        if self.paged {
+            if self.current_page() + 1 < self.total_pages() {
+                self.set_cursor(self.offset + self.page_size);
+            }
+        } else {
+            self.move_cursor(self.page_size as isize);
         }
     }
 
     pub fn prev_page(&mut self) {
-        if self.current_page > 0 {
-            self.current_page -= 1;
+        if self.paged {
+            self.set_cursor(self.offset.saturating_sub(self.page_size));
+        } else {
+            self.move_cursor(-(self.page_size as isize));
         }
     }
 
@@ -0,0 +1,410 @@
+use std::{
+    fs,
+    io::{BufRead, BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
+use sha1::{Digest, Sha1};
+
+use crate::errors::{AppError, AppResult};
+
+// Packing method of the header's first, nameless entry
+const MIME_VERSION: u32 = 0x5665_7273;
+// Packing method of compressed entries
+const MIME_COMPRESSED: u32 = 0x4370_7273;
+
+// Guards against reading a corrupt file as one endless string
+const MAX_STRING_LEN: usize = 4096;
+
+#[derive(Debug, Clone)]
+pub struct PboEntry {
+    pub name: String,
+    pub mime: u32,
+    pub data_size: u32,
+    pub data_offset: u64,
+}
+
+impl PboEntry {
+    pub fn is_compressed(&self) -> bool {
+        self.mime == MIME_COMPRESSED
+    }
+}
+
+#[derive(Debug)]
+pub struct Pbo {
+    pub path: PathBuf,
+    pub properties: Vec<(String, String)>,
+    pub entries: Vec<PboEntry>,
+    // Offset right after the last data block, where the checksum starts
+    pub data_end: u64,
+}
+
+impl Pbo {
+    // Reads the header of a PBO, the file data itself is read on demand
+    pub fn read(path: &Path) -> AppResult<Pbo> {
+        let invalid = || AppError::InvalidPbo(path.to_string_lossy().to_string());
+
+        let mut reader = BufReader::new(fs::File::open(path)?);
+        let mut properties = Vec::new();
+        let mut entries = Vec::new();
+
+        loop {
+            let name = read_asciiz(&mut reader).ok_or_else(invalid)?;
+            let mime = read_u32(&mut reader).ok_or_else(invalid)?;
+            let _original_size = read_u32(&mut reader).ok_or_else(invalid)?;
+            let _reserved = read_u32(&mut reader).ok_or_else(invalid)?;
+            let _timestamp = read_u32(&mut reader).ok_or_else(invalid)?;
+            let data_size = read_u32(&mut reader).ok_or_else(invalid)?;
+
+            if name.is_empty() {
+                if mime == MIME_VERSION {
+                    // Key value pairs until an empty key
+                    loop {
+                        let key = read_asciiz(&mut reader).ok_or_else(invalid)?;
+                        if key.is_empty() {
+                            break;
+                        }
+                        let value = read_asciiz(&mut reader).ok_or_else(invalid)?;
+                        properties.push((key, value));
+                    }
+                    continue;
+                }
+                break;
+            }
+
+            entries.push(PboEntry {
+                name,
+                mime,
+                data_size,
+                data_offset: 0,
+            });
+        }
+
+        let mut offset = reader.stream_position()?;
+        for entry in &mut entries {
+            entry.data_offset = offset;
+            offset += entry.data_size as u64;
+        }
+
+        Ok(Pbo {
+            path: path.to_path_buf(),
+            properties,
+            entries,
+            data_end: offset,
+        })
+    }
+
+    pub fn property(&self, key: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn prefix(&self) -> Option<&str> {
+        self.property("prefix")
+    }
+
+    pub fn read_entry(&self, entry: &PboEntry) -> AppResult<Vec<u8>> {
+        let mut file = fs::File::open(&self.path)?;
+        file.seek(SeekFrom::Start(entry.data_offset))?;
+
+        // The size comes from the header, so the buffer only grows as far as the file goes
+        let mut data = Vec::new();
+        file.take(entry.data_size as u64).read_to_end(&mut data)?;
+
+        if data.len() != entry.data_size as usize {
+            return Err(AppError::InvalidPbo(
+                self.path.to_string_lossy().to_string(),
+            ));
+        }
+
+        Ok(data)
+    }
+
+    // The SHA1 checksum stored after the data, preceded by a zero byte
+    pub fn stored_checksum(&self) -> AppResult<Option<[u8; 20]>> {
+        let mut file = fs::File::open(&self.path)?;
+
+        if file.metadata()?.len() != self.data_end + 21 {
+            return Ok(None);
+        }
+
+        file.seek(SeekFrom::Start(self.data_end))?;
+
+        let mut buf = [0; 21];
+        file.read_exact(&mut buf)?;
+
+        if buf[0] != 0 {
+            return Ok(None);
+        }
+
+        Ok(buf[1..].try_into().ok())
+    }
+
+    // SHA1 over the header and data, which the stored checksum should match
+    pub fn computed_checksum(&self) -> AppResult<[u8; 20]> {
+        let mut reader = BufReader::new(fs::File::open(&self.path)?).take(self.data_end);
+        let mut hasher = Sha1::new();
+        let mut buf = [0; 64 * 1024];
+
+        loop {
+            let len = reader.read(&mut buf)?;
+            if len == 0 {
+                break;
+            }
+            hasher.update(&buf[..len]);
+        }
+
+        Ok(hasher.finalize().into())
+    }
+
+    // Reads the CfgPatches class names from the configs packed in the PBO
+    pub fn cfg_patches(&self) -> AppResult<Vec<String>> {
+        let mut patches = Vec::new();
+
+        for entry in &self.entries {
+            let file_name = entry.name.rsplit('\\').next().unwrap_or_default();
+
+            if entry.is_compressed()
+                || !(file_name.eq_ignore_ascii_case("config.bin")
+                    || file_name.eq_ignore_ascii_case("config.cpp"))
+            {
+                continue;
+            }
+
+            let data = self.read_entry(entry)?;
+
+            if data.starts_with(b"\0raP") {
+                patches.extend(rap_cfg_patches(&data).unwrap_or_default());
+            } else {
+                patches.extend(text_cfg_patches(&String::from_utf8_lossy(&data)));
+            }
+        }
+
+        Ok(patches)
+    }
+}
+
+// Finds all PBOs in the mod's addons folder, whatever its case
+pub fn find_pbos(mod_path: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(mod_path) else {
+        return Vec::new();
+    };
+
+    let mut pbos = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_dir()
+                && p.file_name()
+                    .is_some_and(|n| n.eq_ignore_ascii_case("addons"))
+        })
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("pbo"))
+        })
+        .collect::<Vec<_>>();
+
+    pbos.sort();
+    pbos
+}
+
+fn read_asciiz<R: BufRead>(reader: &mut R) -> Option<String> {
+    let mut buf = Vec::new();
+    reader
+        .take(MAX_STRING_LEN as u64)
+        .read_until(0, &mut buf)
+        .ok()?;
+
+    if buf.pop() != Some(0) {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&buf).to_string())
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Option<u32> {
+    let mut buf = [0; 4];
+    reader.read_exact(&mut buf).ok()?;
+    Some(u32::from_le_bytes(buf))
+}
+
+// Minimal reader for rapified (binarized) configs
+struct Rap<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Rap<'_> {
+    fn byte(&mut self) -> Option<u8> {
+        let b = *self.data.get(self.pos)?;
+        self.pos += 1;
+        Some(b)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        if self.pos + len > self.data.len() {
+            return None;
+        }
+        self.pos += len;
+        Some(())
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let bytes = self.data.get(self.pos..self.pos + 4)?;
+        self.pos += 4;
+        Some(u32::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    fn asciiz(&mut self) -> Option<String> {
+        let len = self.data.get(self.pos..)?.iter().position(|&b| b == 0)?;
+        let s = String::from_utf8_lossy(&self.data[self.pos..self.pos + len]).to_string();
+        self.pos += len + 1;
+        Some(s)
+    }
+
+    fn compressed_int(&mut self) -> Option<u32> {
+        let mut value = 0u32;
+        let mut shift = 0;
+
+        loop {
+            let b = self.byte()?;
+            value |= ((b & 0x7f) as u32).checked_shl(shift)?;
+            if b & 0x80 == 0 {
+                return Some(value);
+            }
+            shift += 7;
+        }
+    }
+
+    fn skip_array(&mut self) -> Option<()> {
+        for _ in 0..self.compressed_int()? {
+            match self.byte()? {
+                0 | 4 => {
+                    self.asciiz()?;
+                }
+                1 | 2 => self.skip(4)?,
+                3 => self.skip_array()?,
+                6 => self.skip(8)?,
+                _ => return None,
+            }
+        }
+        Some(())
+    }
+
+    // Returns the classes defined in the class body at the current position
+    fn classes(&mut self) -> Option<Vec<(String, usize)>> {
+        let mut classes = Vec::new();
+
+        self.asciiz()?; // Parent class
+
+        for _ in 0..self.compressed_int()? {
+            match self.byte()? {
+                0 => {
+                    let name = self.asciiz()?;
+                    let offset = self.u32()? as usize;
+                    classes.push((name, offset));
+                }
+                1 => {
+                    let subtype = self.byte()?;
+                    self.asciiz()?;
+                    match subtype {
+                        0 | 4 => {
+                            self.asciiz()?;
+                        }
+                        1 | 2 => self.skip(4)?,
+                        6 => self.skip(8)?,
+                        _ => return None,
+                    }
+                }
+                2 => {
+                    self.asciiz()?;
+                    self.skip_array()?;
+                }
+                3 | 4 => {
+                    self.asciiz()?;
+                }
+                5 => {
+                    self.skip(4)?;
+                    self.asciiz()?;
+                    self.skip_array()?;
+                }
+                _ => return None,
+            }
+        }
+
+        Some(classes)
+    }
+}
+
+fn rap_cfg_patches(data: &[u8]) -> Option<Vec<String>> {
+    let mut rap = Rap { data, pos: 16 };
+
+    let (_, offset) = rap
+        .classes()?
+        .into_iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("CfgPatches"))?;
+
+    rap.pos = offset;
+
+    Some(rap.classes()?.into_iter().map(|(name, _)| name).collect())
+}
+
+fn text_cfg_patches(text: &str) -> Vec<String> {
+    let mut patches = Vec::new();
+
+    let Some(start) = Regex::new(r"(?i)\bclass\s+CfgPatches\b[^{;]*\{")
+        .unwrap()
+        .find(text)
+    else {
+        return patches;
+    };
+
+    let mut depth = 1;
+    let mut in_string = false;
+    let mut expect_name = false;
+    let mut token = String::new();
+
+    // Collect the names following `class` directly inside CfgPatches
+    for c in text[start.end()..].chars() {
+        if in_string {
+            in_string = c != '"';
+            continue;
+        }
+
+        if c.is_alphanumeric() || c == '_' {
+            token.push(c);
+            continue;
+        }
+
+        if !token.is_empty() {
+            if expect_name {
+                patches.push(token.clone());
+                expect_name = false;
+            } else if depth == 1 && token == "class" {
+                expect_name = true;
+            }
+            token.clear();
+        }
+
+        match c {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    patches
+}
@@ -0,0 +1,106 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::errors::AppResult;
+
+use super::utils;
+
+const LOG_DIR: &str = "arma3-mod-manager-cli-logs";
+
+// A process started in the background with its output written to a log file
+#[derive(Debug)]
+pub struct TrackedProcess {
+    child: Child,
+    started: Instant,
+    log_path: PathBuf,
+}
+
+impl TrackedProcess {
+    pub fn spawn(mut command: Command, log_name: &str) -> AppResult<Self> {
+        let log_path = TrackedProcess::new_log_path(log_name)?;
+
+        let mut log_file = fs::File::create(&log_path)?;
+        writeln!(log_file, "$ {}", display_command(&command))?;
+
+        command
+            .stdin(Stdio::null())
+            .stdout(log_file.try_clone()?)
+            .stderr(log_file);
+
+        Ok(TrackedProcess {
+            child: command.spawn()?,
+            started: Instant::now(),
+            log_path,
+        })
+    }
+
+    fn new_log_path(log_name: &str) -> AppResult<PathBuf> {
+        let log_dir = Path::new(&utils::get_home_path()?).join(LOG_DIR);
+        fs::create_dir_all(&log_dir)?;
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        Ok(log_dir.join(format!("{}-{}.log", log_name, timestamp)))
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.child.id()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub fn log_path(&self) -> &Path {
+        &self.log_path
+    }
+
+    // Returns the exit status once the process has exited, without blocking
+    pub fn try_wait(&mut self) -> AppResult<Option<ExitStatus>> {
+        Ok(self.child.try_wait()?)
+    }
+
+    pub fn kill(&mut self) -> AppResult<()> {
+        self.child.kill()?;
+        self.child.wait()?;
+
+        Ok(())
+    }
+}
+
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        (secs / 60) % 60,
+        secs % 60
+    )
+}
+
+// The command line as shown to the user, with passwords left out
+pub fn display_command(command: &Command) -> String {
+    let mut parts = vec![command.get_program().to_string_lossy().to_string()];
+
+    for arg in command.get_args() {
+        let arg = arg.to_string_lossy();
+
+        match arg.split_once('=') {
+            Some((name, _)) if name.eq_ignore_ascii_case("-password") => {
+                parts.push(format!("{}=********", name))
+            }
+            _ => parts.push(arg.to_string()),
+        }
+    }
+
+    parts.join(" ")
+}
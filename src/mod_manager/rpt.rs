@@ -0,0 +1,147 @@
+use std::{
+    fs,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::errors::AppResult;
+
+use super::utils;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    pub fn of(line: &str) -> Severity {
+        let lower = line.to_lowercase();
+
+        if lower.contains("error") || lower.contains("cannot") || lower.contains("missing") {
+            Severity::Error
+        } else if lower.contains("warning") {
+            Severity::Warning
+        } else {
+            Severity::Info
+        }
+    }
+}
+
+// Folders the game may write its .rpt files to
+//
+// A -profiles= parameter takes precedence, then the configured folder, then the
+// Proton prefix of the Steam library and the default native profile folders
+pub fn rpt_dirs(game_path: &Path, default_args: &str, rpt_path: Option<&str>) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if let Some(profiles) = default_args
+        .split_whitespace()
+        .find_map(|arg| arg.strip_prefix("-profiles="))
+    {
+        dirs.push(PathBuf::from(profiles));
+    }
+
+    if let Some(rpt_path) = rpt_path {
+        dirs.push(PathBuf::from(rpt_path));
+    }
+
+    // <library>/steamapps/common/Arma 3 -> <library>/steamapps
+    if let Some(steamapps) = game_path.parent().and_then(|p| p.parent()) {
+        dirs.push(
+            steamapps.join("compatdata/107410/pfx/drive_c/users/steamuser/AppData/Local/Arma 3"),
+        );
+    }
+
+    if let Ok(home_path) = utils::get_home_path() {
+        let home_path = Path::new(&home_path);
+
+        dirs.push(home_path.join("Library/Application Support/Bohemia Interactive/Arma 3"));
+        dirs.push(home_path.join(".local/share/bohemiainteractive/arma3"));
+    }
+
+    dirs
+}
+
+// Finds the most recently written .rpt file, optionally only ones written since a launch
+pub fn find_newest(dirs: &[PathBuf], since: Option<SystemTime>) -> Option<PathBuf> {
+    dirs.iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("rpt"))
+        })
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .filter(|(_, modified)| since.is_none_or(|since| *modified >= since))
+        .max_by_key(|(_, modified)| *modified)
+        .map(|(path, _)| path)
+}
+
+// Follows a log file, reading only what was appended since the last poll
+#[derive(Debug)]
+pub struct RptTail {
+    path: PathBuf,
+    offset: u64,
+    partial: String,
+    lines: Vec<String>,
+}
+
+impl RptTail {
+    pub fn new(path: PathBuf) -> Self {
+        RptTail {
+            path,
+            offset: 0,
+            partial: String::new(),
+            lines: Vec::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    // Returns true when new lines were read
+    pub fn poll(&mut self) -> AppResult<bool> {
+        let mut file = fs::File::open(&self.path)?;
+        let len = file.metadata()?.len();
+
+        // The file was truncated or replaced, start over
+        if len < self.offset {
+            self.offset = 0;
+            self.partial.clear();
+            self.lines.clear();
+        }
+
+        if len == self.offset {
+            return Ok(false);
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+        self.offset += buf.len() as u64;
+
+        self.partial.push_str(&String::from_utf8_lossy(&buf));
+
+        while let Some(pos) = self.partial.find('\n') {
+            let line = self.partial[..pos].trim_end_matches('\r').to_string();
+            self.partial.drain(..=pos);
+            self.lines.push(line);
+        }
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,412 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{AppError, AppResult};
+
+use super::{
+    deploy::{self, DeployStrategy},
+    file_handler,
+    keys::{self, KeyReport},
+    server_cfg::{BasicCfg, ServerCfg, ServerSettings},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModSide {
+    // Only used by players, the server does not load it
+    Client,
+    // Only loaded by the server through -serverMod=
+    Server,
+    // Loaded by the server through -mod= and required by players
+    Both,
+}
+
+impl ModSide {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ModSide::Client => "C",
+            ModSide::Server => "S",
+            ModSide::Both => "B",
+        }
+    }
+
+    // Order used when cycling through the sides with <SPACE>
+    pub fn next(side: Option<ModSide>) -> Option<ModSide> {
+        match side {
+            None => Some(ModSide::Both),
+            Some(ModSide::Both) => Some(ModSide::Client),
+            Some(ModSide::Client) => Some(ModSide::Server),
+            Some(ModSide::Server) => None,
+        }
+    }
+}
+
+// Fields of the profile form, the mods and server.cfg have screens of their own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileField {
+    Name,
+    BinaryPath,
+    Port,
+    Config,
+    Cfg,
+    Profiles,
+    ProfileName,
+    ExtraArgs,
+}
+
+impl ProfileField {
+    pub const ALL: [ProfileField; 8] = [
+        ProfileField::Name,
+        ProfileField::BinaryPath,
+        ProfileField::Port,
+        ProfileField::Config,
+        ProfileField::Cfg,
+        ProfileField::Profiles,
+        ProfileField::ProfileName,
+        ProfileField::ExtraArgs,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProfileField::Name => "Name",
+            ProfileField::BinaryPath => "Server Binary",
+            ProfileField::Port => "Port",
+            ProfileField::Config => "-config=",
+            ProfileField::Cfg => "-cfg=",
+            ProfileField::Profiles => "-profiles=",
+            ProfileField::ProfileName => "-name=",
+            ProfileField::ExtraArgs => "Extra Arguments",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerProfile {
+    pub name: String,
+    pub binary_path: String,
+    pub port: u16,
+    #[serde(default)]
+    pub config: Option<String>,
+    #[serde(default)]
+    pub cfg: Option<String>,
+    #[serde(default)]
+    pub profiles: Option<String>,
+    #[serde(default)]
+    pub profile_name: Option<String>,
+    #[serde(default)]
+    pub extra_args: String,
+    #[serde(default)]
+    pub mods: BTreeMap<u64, ModSide>,
+    #[serde(default)]
+    pub settings: ServerSettings,
+    // Once set, server.cfg and basic.cfg are generated from settings on every start
+    #[serde(default)]
+    pub manage_cfg: bool,
+    // Number of headless clients started next to the server
+    #[serde(default)]
+    pub headless_clients: usize,
+}
+
+impl ServerProfile {
+    pub fn new(name: String, game_path: &Path) -> Self {
+        // The dedicated server is a separate Steam app installed next to the game
+        let binary_path = game_path
+            .parent()
+            .unwrap_or(game_path)
+            .join("Arma 3 Server/arma3server_x64");
+
+        ServerProfile {
+            name,
+            binary_path: binary_path.to_string_lossy().to_string(),
+            port: 2302,
+            config: None,
+            cfg: None,
+            profiles: None,
+            profile_name: None,
+            extra_args: String::new(),
+            mods: BTreeMap::new(),
+            settings: ServerSettings::default(),
+            manage_cfg: false,
+            headless_clients: 0,
+        }
+    }
+
+    pub fn get_field(&self, field: ProfileField) -> String {
+        match field {
+            ProfileField::Name => self.name.clone(),
+            ProfileField::BinaryPath => self.binary_path.clone(),
+            ProfileField::Port => self.port.to_string(),
+            ProfileField::Config => self.config.clone().unwrap_or_default(),
+            ProfileField::Cfg => self.cfg.clone().unwrap_or_default(),
+            ProfileField::Profiles => self.profiles.clone().unwrap_or_default(),
+            ProfileField::ProfileName => self.profile_name.clone().unwrap_or_default(),
+            ProfileField::ExtraArgs => self.extra_args.clone(),
+        }
+    }
+
+    // The names of the other profiles are passed in, running servers are tracked by name.
+    // An empty value leaves an optional argument out
+    pub fn set_field(
+        &mut self,
+        taken: &[String],
+        field: ProfileField,
+        value: &str,
+    ) -> AppResult<()> {
+        let invalid = || AppError::InvalidCfgValue(field.label().to_string(), value.to_string());
+        let value = value.trim();
+        let optional = || (!value.is_empty()).then(|| value.to_string());
+
+        match field {
+            ProfileField::Name => {
+                if value.is_empty() {
+                    return Err(invalid());
+                }
+                if taken.iter().any(|t| t == value) {
+                    return Err(AppError::NameTaken(value.to_string()));
+                }
+
+                self.name = value.to_string();
+            }
+            ProfileField::BinaryPath => {
+                if value.is_empty() {
+                    return Err(invalid());
+                }
+
+                self.binary_path = value.to_string();
+            }
+            ProfileField::Port => self.port = value.parse().map_err(|_| invalid())?,
+            ProfileField::Config => self.config = optional(),
+            ProfileField::Cfg => self.cfg = optional(),
+            ProfileField::Profiles => self.profiles = optional(),
+            ProfileField::ProfileName => self.profile_name = optional(),
+            ProfileField::ExtraArgs => self.extra_args = value.to_string(),
+        }
+
+        Ok(())
+    }
+
+    pub fn get_binary_path(&self) -> &Path {
+        Path::new(&self.binary_path)
+    }
+
+    pub fn get_server_dir(&self) -> &Path {
+        self.get_binary_path().parent().unwrap_or(Path::new("."))
+    }
+
+    pub fn get_server_cfg_path(&self) -> PathBuf {
+        match &self.config {
+            Some(path) => self.get_server_dir().join(path),
+            None => self.get_server_dir().join("server.cfg"),
+        }
+    }
+
+    pub fn get_basic_cfg_path(&self) -> PathBuf {
+        match &self.cfg {
+            Some(path) => self.get_server_dir().join(path),
+            None => self.get_server_dir().join("basic.cfg"),
+        }
+    }
+
+    // Writes server.cfg and basic.cfg and points the profile at them
+    pub fn write_cfg_files(&mut self) -> AppResult<()> {
+        let server_cfg_path = self.get_server_cfg_path();
+        let basic_cfg_path = self.get_basic_cfg_path();
+
+        // Holds the passwords
+        file_handler::write_private(&server_cfg_path, &self.settings.server.to_cfg())?;
+        fs::write(&basic_cfg_path, self.settings.basic.to_cfg())?;
+
+        self.config = Some(server_cfg_path.to_string_lossy().to_string());
+        self.cfg = Some(basic_cfg_path.to_string_lossy().to_string());
+        self.manage_cfg = true;
+
+        Ok(())
+    }
+
+    // Reads the existing server.cfg and basic.cfg into settings
+    pub fn import_cfg_files(&mut self) -> AppResult<()> {
+        let server_cfg_path = self.get_server_cfg_path();
+        let basic_cfg_path = self.get_basic_cfg_path();
+
+        if !server_cfg_path.exists() && !basic_cfg_path.exists() {
+            return Err(AppError::InvalidPath(
+                server_cfg_path.to_string_lossy().into(),
+            ));
+        }
+
+        if server_cfg_path.exists() {
+            self.settings.server = ServerCfg::parse(&fs::read_to_string(&server_cfg_path)?)?;
+        }
+        if basic_cfg_path.exists() {
+            self.settings.basic = BasicCfg::parse(&fs::read_to_string(&basic_cfg_path)?)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn mod_ids(&self, side: ModSide) -> Vec<u64> {
+        self.mods
+            .iter()
+            .filter(|(_, s)| **s == side)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    pub fn set_side(&mut self, id: u64, side: Option<ModSide>) {
+        match side {
+            Some(side) => self.mods.insert(id, side),
+            None => self.mods.remove(&id),
+        };
+    }
+
+    pub fn get_keys_dir(&self) -> PathBuf {
+        self.get_server_dir().join("keys")
+    }
+
+    // Collects the keys of every mod players load into the server's keys folder
+    pub fn collect_keys(
+        &self,
+        workshop_path: &Path,
+        installed: &[u64],
+        strategy: DeployStrategy,
+    ) -> AppResult<KeyReport> {
+        let mods = self
+            .mods
+            .iter()
+            .filter(|(id, side)| **side != ModSide::Server && installed.contains(id))
+            .map(|(id, _)| (*id, workshop_path.join(id.to_string())))
+            .collect::<Vec<_>>();
+
+        keys::sync_keys(&self.get_keys_dir(), &mods, strategy)
+    }
+
+    // Deploys the server's mods into its directory and builds the launch command
+    //
+    // Mods that are no longer installed are left out
+    pub fn build_command(
+        &self,
+        workshop_path: &Path,
+        installed: &[u64],
+        strategy: DeployStrategy,
+        lowercase: bool,
+    ) -> AppResult<Command> {
+        let binary_path = self.get_binary_path();
+
+        if !binary_path.exists() {
+            return Err(AppError::InvalidPath(binary_path.to_string_lossy().into()));
+        }
+
+        let mods = self
+            .mod_ids(ModSide::Both)
+            .into_iter()
+            .filter(|id| installed.contains(id))
+            .collect::<Vec<_>>();
+        let server_mods = self
+            .mod_ids(ModSide::Server)
+            .into_iter()
+            .filter(|id| installed.contains(id))
+            .collect::<Vec<_>>();
+
+        let mod_paths = mods
+            .iter()
+            .chain(&server_mods)
+            .map(|id| workshop_path.join(id.to_string()))
+            .collect::<Vec<PathBuf>>();
+
+        deploy::deploy(strategy, lowercase, self.get_server_dir(), mod_paths)?;
+
+        let mut command = Command::new(binary_path);
+        command.current_dir(self.get_server_dir());
+
+        command.arg(format!("-port={}", self.port));
+
+        let optional_args = [
+            ("-config=", &self.config),
+            ("-cfg=", &self.cfg),
+            ("-profiles=", &self.profiles),
+            ("-name=", &self.profile_name),
+        ];
+        for (arg, value) in optional_args {
+            if let Some(value) = value {
+                command.arg(format!("{}{}", arg, value));
+            }
+        }
+
+        command.args(self.extra_args.split_whitespace());
+
+        if !mods.is_empty() {
+            command.arg(format!("-mod={}", join_ids(&mods)));
+        }
+        if !server_mods.is_empty() {
+            command.arg(format!("-serverMod={}", join_ids(&server_mods)));
+        }
+
+        Ok(command)
+    }
+
+    // Builds the command for a headless client connecting to this server
+    //
+    // The mods are passed with their full workshop path, deploying them into the server
+    // directory would replace the ones the server itself was started with
+    pub fn build_headless_command(
+        &self,
+        workshop_path: &Path,
+        mod_ids: &[u64],
+        index: usize,
+    ) -> AppResult<Command> {
+        let binary_path = self.get_binary_path();
+
+        if !binary_path.exists() {
+            return Err(AppError::InvalidPath(binary_path.to_string_lossy().into()));
+        }
+
+        let mut command = Command::new(binary_path);
+        command.current_dir(self.get_server_dir());
+
+        command.arg("-client");
+        command.arg("-connect=127.0.0.1");
+        command.arg(format!("-port={}", self.port));
+        command.arg(format!("-name=hc{}", index + 1));
+
+        // Arguments are visible to every user in the process list, a server.cfg we wrote lets
+        // local headless clients in without the password, only other configs still need it
+        let password = &self.settings.server.password;
+        let trusted = self.manage_cfg && self.settings.server.trusts_localhost();
+        if !password.is_empty() && !trusted {
+            command.arg(format!("-password={}", password));
+        }
+
+        if let Some(profiles) = &self.profiles {
+            command.arg(format!("-profiles={}", profiles));
+        }
+
+        if !mod_ids.is_empty() {
+            let mod_paths = mod_ids
+                .iter()
+                .map(|id| {
+                    workshop_path
+                        .join(id.to_string())
+                        .to_string_lossy()
+                        .to_string()
+                })
+                .collect::<Vec<_>>();
+
+            command.arg(format!("-mod={}", mod_paths.join(";")));
+        }
+
+        Ok(command)
+    }
+}
+
+fn join_ids(ids: &[u64]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(";")
+}
@@ -0,0 +1,963 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{AppError, AppResult};
+
+use super::{file_handler, server::ServerProfile, utils};
+
+const PASSWORDS_FILE: &str = "arma3-mod-manager-cli-server-passwords.json";
+// Where the headless clients started by the manager connect from
+const LOCALHOST: &str = "127.0.0.1";
+
+// Settings written to server.cfg
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerCfg {
+    pub hostname: String,
+    // The passwords are kept in ServerPasswords, still read here from older configs
+    #[serde(skip_serializing)]
+    pub password: String,
+    #[serde(skip_serializing)]
+    pub password_admin: String,
+    #[serde(skip_serializing)]
+    pub password_command: String,
+    pub max_players: u32,
+    pub missions: Vec<Mission>,
+    // Used by the missions that don't set their own
+    pub difficulty: String,
+    pub verify_signatures: u32,
+    pub battle_eye: bool,
+    pub motd: Vec<String>,
+    pub motd_interval: u32,
+    // Addresses headless clients may connect from, and the ones among them sharing the
+    // server's machine
+    pub headless_client_ips: Vec<String>,
+    pub local_client_ips: Vec<String>,
+    // Statements we don't model, kept as written so importing doesn't lose them
+    pub extra: Vec<String>,
+}
+
+impl Default for ServerCfg {
+    fn default() -> Self {
+        ServerCfg {
+            hostname: "Arma 3 Server".to_string(),
+            password: String::new(),
+            password_admin: String::new(),
+            password_command: String::new(),
+            max_players: 32,
+            missions: Vec::new(),
+            difficulty: "Regular".to_string(),
+            verify_signatures: 2,
+            battle_eye: true,
+            motd: Vec::new(),
+            motd_interval: 5,
+            headless_client_ips: vec![LOCALHOST.to_string()],
+            local_client_ips: vec![LOCALHOST.to_string()],
+            extra: Vec::new(),
+        }
+    }
+}
+
+impl ServerCfg {
+    pub fn to_cfg(&self) -> String {
+        let mut out = String::new();
+
+        out += &format!("hostname = {};\n", quote(&self.hostname));
+        out += &format!("password = {};\n", quote(&self.password));
+        out += &format!("passwordAdmin = {};\n", quote(&self.password_admin));
+        if !self.password_command.is_empty() {
+            out += &format!(
+                "serverCommandPassword = {};\n",
+                quote(&self.password_command)
+            );
+        }
+        out += &format!("maxPlayers = {};\n", self.max_players);
+        out += &format!("verifySignatures = {};\n", self.verify_signatures);
+        out += &format!("BattlEye = {};\n", self.battle_eye as u8);
+        out += &format!("motd[] = {{{}}};\n", quote_list(&self.motd));
+        out += &format!("motdInterval = {};\n", self.motd_interval);
+        if !self.headless_client_ips.is_empty() {
+            out += &format!(
+                "headlessClients[] = {{{}}};\n",
+                quote_list(&self.headless_client_ips)
+            );
+        }
+        if !self.local_client_ips.is_empty() {
+            out += &format!(
+                "localClient[] = {{{}}};\n",
+                quote_list(&self.local_client_ips)
+            );
+        }
+
+        for statement in &self.extra {
+            out += statement;
+            out += "\n";
+        }
+
+        out += "\nclass Missions\n{\n";
+        for (i, mission) in self.missions.iter().enumerate() {
+            let difficulty = mission.difficulty.as_deref().unwrap_or(&self.difficulty);
+
+            out += &format!("    class Mission{}\n    {{\n", i + 1);
+            out += &format!("        template = {};\n", quote(&mission.template));
+            out += &format!("        difficulty = {};\n", quote(difficulty));
+            for line in mission.extra.iter().flat_map(|s| s.lines()) {
+                out += &format!("        {}\n", line);
+            }
+            out += "    };\n";
+        }
+        out += "};\n";
+
+        out
+    }
+
+    pub fn parse(text: &str) -> AppResult<ServerCfg> {
+        let mut cfg = ServerCfg {
+            missions: Vec::new(),
+            motd: Vec::new(),
+            headless_client_ips: Vec::new(),
+            local_client_ips: Vec::new(),
+            ..ServerCfg::default()
+        };
+
+        for (name, node) in CfgParser::new(text).parse()? {
+            match (name.to_lowercase().as_str(), &node) {
+                ("hostname", _) => cfg.hostname = node.as_string(),
+                ("password", _) => cfg.password = node.as_string(),
+                ("passwordadmin", _) => cfg.password_admin = node.as_string(),
+                ("servercommandpassword", _) => cfg.password_command = node.as_string(),
+                ("maxplayers", _) => cfg.max_players = node.as_number(&name)?,
+                ("verifysignatures", _) => cfg.verify_signatures = node.as_number(&name)?,
+                ("battleye", _) => cfg.battle_eye = node.as_number::<u32>(&name)? != 0,
+                ("motd", CfgNode::Array(items)) => {
+                    cfg.motd = items.iter().map(|i| i.as_string()).collect()
+                }
+                ("motdinterval", _) => cfg.motd_interval = node.as_number(&name)?,
+                ("headlessclients", CfgNode::Array(items)) => {
+                    cfg.headless_client_ips = items.iter().map(|i| i.as_string()).collect()
+                }
+                ("localclient", CfgNode::Array(items)) => {
+                    cfg.local_client_ips = items.iter().map(|i| i.as_string()).collect()
+                }
+                ("missions", CfgNode::Class(missions)) => {
+                    for (_, mission) in missions {
+                        if let CfgNode::Class(entries) = mission {
+                            cfg.missions.push(Mission::parse(entries));
+                        }
+                    }
+
+                    // The first mission's difficulty becomes the default, the others only
+                    // keep their own when it differs
+                    if let Some(difficulty) =
+                        cfg.missions.first().and_then(|m| m.difficulty.clone())
+                    {
+                        for mission in &mut cfg.missions {
+                            if mission.difficulty.as_ref() == Some(&difficulty) {
+                                mission.difficulty = None;
+                            }
+                        }
+                        cfg.difficulty = difficulty;
+                    }
+                }
+                _ => cfg.extra.push(node.to_statement(&name)),
+            }
+        }
+
+        Ok(cfg)
+    }
+
+    // Whether headless clients started next to the server are let in without the password
+    pub fn trusts_localhost(&self) -> bool {
+        [&self.headless_client_ips, &self.local_client_ips]
+            .iter()
+            .all(|list| list.iter().any(|a| a == LOCALHOST))
+    }
+}
+
+// A mission of the cycle, with the settings the form doesn't cover kept as written, like
+// its Params
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "MissionEntry")]
+pub struct Mission {
+    pub template: String,
+    pub difficulty: Option<String>,
+    pub extra: Vec<String>,
+}
+
+impl Mission {
+    pub fn new(template: String) -> Self {
+        Mission {
+            template,
+            difficulty: None,
+            extra: Vec::new(),
+        }
+    }
+
+    fn parse(entries: &[(String, CfgNode)]) -> Mission {
+        let mut mission = Mission::new(String::new());
+
+        for (key, value) in entries {
+            if key.eq_ignore_ascii_case("template") {
+                mission.template = value.as_string();
+            } else if key.eq_ignore_ascii_case("difficulty") {
+                mission.difficulty = Some(value.as_string());
+            } else {
+                mission.extra.push(value.to_statement(key));
+            }
+        }
+
+        mission
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MissionEntry {
+    // Configs from before missions kept their own settings only have the template
+    Template(String),
+    Mission {
+        template: String,
+        #[serde(default)]
+        difficulty: Option<String>,
+        #[serde(default)]
+        extra: Vec<String>,
+    },
+}
+
+impl From<MissionEntry> for Mission {
+    fn from(entry: MissionEntry) -> Self {
+        match entry {
+            MissionEntry::Template(template) => Mission::new(template),
+            MissionEntry::Mission {
+                template,
+                difficulty,
+                extra,
+            } => Mission {
+                template,
+                difficulty,
+                extra,
+            },
+        }
+    }
+}
+
+// Settings written to basic.cfg, mostly network tuning
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BasicCfg {
+    pub max_msg_send: u32,
+    pub max_size_guaranteed: u32,
+    pub max_size_nonguaranteed: u32,
+    pub min_bandwidth: u64,
+    pub max_bandwidth: u64,
+    pub min_error_to_send: String,
+    pub min_error_to_send_near: String,
+    pub max_custom_file_size: u32,
+    pub extra: Vec<String>,
+}
+
+impl Default for BasicCfg {
+    fn default() -> Self {
+        BasicCfg {
+            max_msg_send: 128,
+            max_size_guaranteed: 512,
+            max_size_nonguaranteed: 256,
+            min_bandwidth: 131072,
+            max_bandwidth: 10000000000,
+            min_error_to_send: "0.001".to_string(),
+            min_error_to_send_near: "0.01".to_string(),
+            max_custom_file_size: 0,
+            extra: Vec::new(),
+        }
+    }
+}
+
+impl BasicCfg {
+    pub fn to_cfg(&self) -> String {
+        let mut out = String::new();
+
+        out += &format!("MaxMsgSend = {};\n", self.max_msg_send);
+        out += &format!("MaxSizeGuaranteed = {};\n", self.max_size_guaranteed);
+        out += &format!("MaxSizeNonguaranteed = {};\n", self.max_size_nonguaranteed);
+        out += &format!("MinBandwidth = {};\n", self.min_bandwidth);
+        out += &format!("MaxBandwidth = {};\n", self.max_bandwidth);
+        out += &format!("MinErrorToSend = {};\n", self.min_error_to_send);
+        out += &format!("MinErrorToSendNear = {};\n", self.min_error_to_send_near);
+        out += &format!("MaxCustomFileSize = {};\n", self.max_custom_file_size);
+
+        for statement in &self.extra {
+            out += statement;
+            out += "\n";
+        }
+
+        out
+    }
+
+    pub fn parse(text: &str) -> AppResult<BasicCfg> {
+        let mut cfg = BasicCfg::default();
+
+        for (name, node) in CfgParser::new(text).parse()? {
+            match name.to_lowercase().as_str() {
+                "maxmsgsend" => cfg.max_msg_send = node.as_number(&name)?,
+                "maxsizeguaranteed" => cfg.max_size_guaranteed = node.as_number(&name)?,
+                "maxsizenonguaranteed" => cfg.max_size_nonguaranteed = node.as_number(&name)?,
+                "minbandwidth" => cfg.min_bandwidth = node.as_number(&name)?,
+                "maxbandwidth" => cfg.max_bandwidth = node.as_number(&name)?,
+                "minerrortosend" => cfg.min_error_to_send = node.as_string(),
+                "minerrortosendnear" => cfg.min_error_to_send_near = node.as_string(),
+                "maxcustomfilesize" => cfg.max_custom_file_size = node.as_number(&name)?,
+                _ => cfg.extra.push(node.to_statement(&name)),
+            }
+        }
+
+        Ok(cfg)
+    }
+}
+
+// The fields that can be edited in the server settings form
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CfgField {
+    Hostname,
+    Password,
+    PasswordAdmin,
+    PasswordCommand,
+    MaxPlayers,
+    Missions,
+    Difficulty,
+    VerifySignatures,
+    BattlEye,
+    Motd,
+    MotdInterval,
+    MaxMsgSend,
+    MaxSizeGuaranteed,
+    MaxSizeNonguaranteed,
+    MinBandwidth,
+    MaxBandwidth,
+    MinErrorToSend,
+    MinErrorToSendNear,
+    MaxCustomFileSize,
+}
+
+impl CfgField {
+    pub const ALL: [CfgField; 19] = [
+        CfgField::Hostname,
+        CfgField::Password,
+        CfgField::PasswordAdmin,
+        CfgField::PasswordCommand,
+        CfgField::MaxPlayers,
+        CfgField::Missions,
+        CfgField::Difficulty,
+        CfgField::VerifySignatures,
+        CfgField::BattlEye,
+        CfgField::Motd,
+        CfgField::MotdInterval,
+        CfgField::MaxMsgSend,
+        CfgField::MaxSizeGuaranteed,
+        CfgField::MaxSizeNonguaranteed,
+        CfgField::MinBandwidth,
+        CfgField::MaxBandwidth,
+        CfgField::MinErrorToSend,
+        CfgField::MinErrorToSendNear,
+        CfgField::MaxCustomFileSize,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CfgField::Hostname => "Hostname",
+            CfgField::Password => "Password",
+            CfgField::PasswordAdmin => "Admin Password",
+            CfgField::PasswordCommand => "Command Password",
+            CfgField::MaxPlayers => "Max Players",
+            CfgField::Missions => "Mission Cycle",
+            CfgField::Difficulty => "Difficulty",
+            CfgField::VerifySignatures => "Verify Signatures",
+            CfgField::BattlEye => "BattlEye",
+            CfgField::Motd => "MOTD",
+            CfgField::MotdInterval => "MOTD Interval",
+            CfgField::MaxMsgSend => "Max Msg Send",
+            CfgField::MaxSizeGuaranteed => "Max Size Guaranteed",
+            CfgField::MaxSizeNonguaranteed => "Max Size Nonguaranteed",
+            CfgField::MinBandwidth => "Min Bandwidth",
+            CfgField::MaxBandwidth => "Max Bandwidth",
+            CfgField::MinErrorToSend => "Min Error To Send",
+            CfgField::MinErrorToSendNear => "Min Error To Send Near",
+            CfgField::MaxCustomFileSize => "Max Custom File Size",
+        }
+    }
+
+    pub fn is_secret(&self) -> bool {
+        matches!(
+            self,
+            CfgField::Password | CfgField::PasswordAdmin | CfgField::PasswordCommand
+        )
+    }
+
+    pub fn is_bool(&self) -> bool {
+        matches!(self, CfgField::BattlEye)
+    }
+}
+
+// server.cfg and basic.cfg of a server profile
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerSettings {
+    pub server: ServerCfg,
+    pub basic: BasicCfg,
+}
+
+impl ServerSettings {
+    // Lists are shown and edited separated by " | "
+    pub fn get(&self, field: CfgField) -> String {
+        let (server, basic) = (&self.server, &self.basic);
+
+        match field {
+            CfgField::Hostname => server.hostname.clone(),
+            CfgField::Password => server.password.clone(),
+            CfgField::PasswordAdmin => server.password_admin.clone(),
+            CfgField::PasswordCommand => server.password_command.clone(),
+            CfgField::MaxPlayers => server.max_players.to_string(),
+            CfgField::Missions => server
+                .missions
+                .iter()
+                .map(|m| m.template.as_str())
+                .collect::<Vec<_>>()
+                .join(" | "),
+            CfgField::Difficulty => server.difficulty.clone(),
+            CfgField::VerifySignatures => server.verify_signatures.to_string(),
+            CfgField::BattlEye => server.battle_eye.to_string(),
+            CfgField::Motd => server.motd.join(" | "),
+            CfgField::MotdInterval => server.motd_interval.to_string(),
+            CfgField::MaxMsgSend => basic.max_msg_send.to_string(),
+            CfgField::MaxSizeGuaranteed => basic.max_size_guaranteed.to_string(),
+            CfgField::MaxSizeNonguaranteed => basic.max_size_nonguaranteed.to_string(),
+            CfgField::MinBandwidth => basic.min_bandwidth.to_string(),
+            CfgField::MaxBandwidth => basic.max_bandwidth.to_string(),
+            CfgField::MinErrorToSend => basic.min_error_to_send.clone(),
+            CfgField::MinErrorToSendNear => basic.min_error_to_send_near.clone(),
+            CfgField::MaxCustomFileSize => basic.max_custom_file_size.to_string(),
+        }
+    }
+
+    pub fn set(&mut self, field: CfgField, value: &str) -> AppResult<()> {
+        let (server, basic) = (&mut self.server, &mut self.basic);
+        let label = field.label();
+        let value = value.trim();
+
+        match field {
+            CfgField::Hostname => server.hostname = value.to_string(),
+            CfgField::Password => server.password = value.to_string(),
+            CfgField::PasswordAdmin => server.password_admin = value.to_string(),
+            CfgField::PasswordCommand => server.password_command = value.to_string(),
+            CfgField::MaxPlayers => server.max_players = parse_number(label, value)?,
+            CfgField::Missions => {
+                // Missions still in the cycle keep their own settings
+                server.missions = split_list(value)
+                    .into_iter()
+                    .map(
+                        |template| match server.missions.iter().find(|m| m.template == template) {
+                            Some(mission) => mission.clone(),
+                            None => Mission::new(template),
+                        },
+                    )
+                    .collect();
+            }
+            CfgField::Difficulty => server.difficulty = value.to_string(),
+            CfgField::VerifySignatures => server.verify_signatures = parse_number(label, value)?,
+            CfgField::BattlEye => server.battle_eye = parse_number::<bool>(label, value)?,
+            CfgField::Motd => server.motd = split_list(value),
+            CfgField::MotdInterval => server.motd_interval = parse_number(label, value)?,
+            CfgField::MaxMsgSend => basic.max_msg_send = parse_number(label, value)?,
+            CfgField::MaxSizeGuaranteed => basic.max_size_guaranteed = parse_number(label, value)?,
+            CfgField::MaxSizeNonguaranteed => {
+                basic.max_size_nonguaranteed = parse_number(label, value)?
+            }
+            CfgField::MinBandwidth => basic.min_bandwidth = parse_number(label, value)?,
+            CfgField::MaxBandwidth => basic.max_bandwidth = parse_number(label, value)?,
+            CfgField::MinErrorToSend => {
+                basic.min_error_to_send = parse_number::<f64>(label, value)?.to_string()
+            }
+            CfgField::MinErrorToSendNear => {
+                basic.min_error_to_send_near = parse_number::<f64>(label, value)?.to_string()
+            }
+            CfgField::MaxCustomFileSize => basic.max_custom_file_size = parse_number(label, value)?,
+        }
+
+        Ok(())
+    }
+}
+
+// The passwords of each server profile's server.cfg by profile name
+//
+// Kept out of the config in a file only the user can read, like the connection passwords
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ServerPasswords {
+    profiles: HashMap<String, [String; 3]>,
+}
+
+impl ServerPasswords {
+    fn get_save_path() -> AppResult<PathBuf> {
+        let home_path = utils::get_home_path()?;
+
+        Ok(Path::new(&home_path).join(PASSWORDS_FILE))
+    }
+
+    pub fn read() -> ServerPasswords {
+        ServerPasswords::get_save_path()
+            .and_then(|path| file_handler::read_json(&path))
+            .unwrap_or_default()
+    }
+
+    pub fn from_profiles(profiles: &[ServerProfile]) -> ServerPasswords {
+        let profiles = profiles
+            .iter()
+            .map(|profile| {
+                let server = &profile.settings.server;
+                let passwords = [
+                    server.password.clone(),
+                    server.password_admin.clone(),
+                    server.password_command.clone(),
+                ];
+
+                (profile.name.clone(), passwords)
+            })
+            .filter(|(_, passwords)| passwords.iter().any(|p| !p.is_empty()))
+            .collect();
+
+        ServerPasswords { profiles }
+    }
+
+    // Fills in the passwords of the profiles, leaving the ones read from an older config
+    // when there are none stored yet
+    pub fn apply(&self, profiles: &mut [ServerProfile]) {
+        for profile in profiles {
+            if let Some([password, admin, command]) = self.profiles.get(&profile.name) {
+                let server = &mut profile.settings.server;
+                server.password = password.clone();
+                server.password_admin = admin.clone();
+                server.password_command = command.clone();
+            }
+        }
+    }
+
+    pub fn save(&self) -> AppResult<()> {
+        let path = ServerPasswords::get_save_path()?;
+
+        // Nothing to keep, and no file with old passwords to clear
+        if self.profiles.is_empty() && !path.exists() {
+            return Ok(());
+        }
+
+        file_handler::write_json_private(&path, self)
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(field: &str, value: &str) -> AppResult<T> {
+    value
+        .parse()
+        .map_err(|_| AppError::InvalidCfgValue(field.to_string(), value.to_string()))
+}
+
+fn parse_error(message: &str) -> AppError {
+    AppError::CfgParseError(message.to_string())
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split('|')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+fn quote_list(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|v| quote(v))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Debug, Clone)]
+enum CfgNode {
+    // A number or other bare token
+    Value(String),
+    String(String),
+    Array(Vec<CfgNode>),
+    Class(Vec<(String, CfgNode)>),
+}
+
+impl CfgNode {
+    fn as_string(&self) -> String {
+        match self {
+            CfgNode::Value(value) | CfgNode::String(value) => value.clone(),
+            CfgNode::Array(items) => items
+                .iter()
+                .map(|i| i.as_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            CfgNode::Class(_) => String::new(),
+        }
+    }
+
+    fn as_number<T: std::str::FromStr>(&self, name: &str) -> AppResult<T> {
+        parse_number(name, &self.as_string())
+    }
+
+    fn to_raw(&self) -> String {
+        match self {
+            CfgNode::Value(value) => value.clone(),
+            CfgNode::String(value) => quote(value),
+            CfgNode::Array(items) => format!(
+                "{{{}}}",
+                items
+                    .iter()
+                    .map(|i| i.to_raw())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            CfgNode::Class(entries) => format!(
+                "{{\n{}}}",
+                entries
+                    .iter()
+                    .map(|(name, node)| format!("{}\n", node.to_statement(name)))
+                    .collect::<String>()
+            ),
+        }
+    }
+
+    fn to_statement(&self, name: &str) -> String {
+        match self {
+            CfgNode::Value(_) | CfgNode::String(_) => {
+                format!("{} = {};", name, self.to_raw())
+            }
+            CfgNode::Array(_) => format!("{}[] = {};", name, self.to_raw()),
+            CfgNode::Class(_) => format!("class {}\n{};", name, self.to_raw()),
+        }
+    }
+}
+
+// Parser for the config syntax used by server.cfg and basic.cfg
+struct CfgParser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> CfgParser<'a> {
+    fn new(text: &'a str) -> Self {
+        CfgParser {
+            chars: text.chars().peekable(),
+        }
+    }
+
+    fn parse(&mut self) -> AppResult<Vec<(String, CfgNode)>> {
+        let entries = self.entries()?;
+
+        match self.chars.peek() {
+            None => Ok(entries),
+            Some(c) => Err(parse_error(&format!("unexpected '{}'", c))),
+        }
+    }
+
+    // Skips whitespace, comments and preprocessor lines
+    fn skip(&mut self) {
+        loop {
+            match self.chars.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.chars.next();
+                }
+                Some('#') => {
+                    self.chars.by_ref().find(|&c| c == '\n');
+                }
+                Some('/') => {
+                    let mut lookahead = self.chars.clone();
+                    lookahead.next();
+
+                    match lookahead.next() {
+                        Some('/') => {
+                            self.chars.by_ref().find(|&c| c == '\n');
+                        }
+                        Some('*') => {
+                            self.chars.next();
+                            self.chars.next();
+                            let mut last = ' ';
+                            for c in self.chars.by_ref() {
+                                if last == '*' && c == '/' {
+                                    break;
+                                }
+                                last = c;
+                            }
+                        }
+                        _ => return,
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> AppResult<()> {
+        self.skip();
+
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(parse_error(&format!(
+                "expected '{}', found '{}'",
+                expected, c
+            ))),
+            None => Err(parse_error(&format!("expected '{}'", expected))),
+        }
+    }
+
+    fn identifier(&mut self) -> String {
+        self.skip();
+
+        let mut ident = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !(c.is_alphanumeric() || c == '_') {
+                break;
+            }
+            ident.push(c);
+            self.chars.next();
+        }
+
+        ident
+    }
+
+    // Entries until the end of the input or the closing brace of a class
+    fn entries(&mut self) -> AppResult<Vec<(String, CfgNode)>> {
+        let mut entries = Vec::new();
+
+        loop {
+            self.skip();
+
+            match self.chars.peek() {
+                None | Some('}') => return Ok(entries),
+                Some(';') => {
+                    self.chars.next();
+                    continue;
+                }
+                _ => {}
+            }
+
+            let name = self.identifier();
+            if name.is_empty() {
+                let c = self.chars.peek().copied().unwrap_or(' ');
+                return Err(parse_error(&format!("unexpected '{}'", c)));
+            }
+
+            if name == "class" {
+                let class_name = self.identifier();
+
+                self.skip();
+                if self.chars.peek() == Some(&':') {
+                    self.chars.next();
+                    self.identifier();
+                }
+
+                self.skip();
+                if self.chars.peek() == Some(&';') {
+                    // Forward declaration
+                    continue;
+                }
+
+                self.expect('{')?;
+                let body = self.entries()?;
+                self.expect('}')?;
+
+                entries.push((class_name, CfgNode::Class(body)));
+                continue;
+            }
+
+            self.skip();
+            let is_array = self.chars.peek() == Some(&'[');
+            if is_array {
+                self.expect('[')?;
+                self.expect(']')?;
+                self.skip();
+                // += appends, which is the same as assigning for a single file
+                if self.chars.peek() == Some(&'+') {
+                    self.chars.next();
+                }
+            }
+
+            self.expect('=')?;
+
+            let node = if is_array {
+                self.array()?
+            } else {
+                self.value()?
+            };
+
+            entries.push((name, node));
+        }
+    }
+
+    fn array(&mut self) -> AppResult<CfgNode> {
+        self.expect('{')?;
+
+        let mut items = Vec::new();
+
+        loop {
+            self.skip();
+
+            match self.chars.peek() {
+                Some('}') => {
+                    self.chars.next();
+                    return Ok(CfgNode::Array(items));
+                }
+                Some(',') => {
+                    self.chars.next();
+                }
+                Some('{') => items.push(self.array()?),
+                // Ends a value without being consumed by it, the closing brace is missing
+                Some(';') | None => return Err(parse_error("unterminated array")),
+                Some(_) => items.push(self.value()?),
+            }
+        }
+    }
+
+    fn value(&mut self) -> AppResult<CfgNode> {
+        self.skip();
+
+        if self.chars.peek() == Some(&'"') {
+            self.chars.next();
+
+            let mut value = String::new();
+            loop {
+                match self.chars.next() {
+                    // "" is an escaped quote
+                    Some('"') if self.chars.peek() == Some(&'"') => {
+                        self.chars.next();
+                        value.push('"');
+                    }
+                    Some('"') => return Ok(CfgNode::String(value)),
+                    Some(c) => value.push(c),
+                    None => return Err(parse_error("unterminated string")),
+                }
+            }
+        }
+
+        let mut value = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c == ';' || c == ',' || c == '}' || c == '\n' {
+                break;
+            }
+            value.push(c);
+            self.chars.next();
+        }
+
+        Ok(CfgNode::Value(value.trim().to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERVER_CFG: &str = r#"
+// Written by hand
+#include "common.hpp"
+hostname = "The ""Best"" Server"; /* quotes are doubled */
+password = "secret";
+maxPlayers = 64;
+motd[] = {"Welcome", "Have fun, and ""behave"""};
+headlessClients[] = {"127.0.0.1", "10.0.0.2"};
+localClient[] = {"127.0.0.1"};
+allowedFilePatching = 1;
+kickDuplicate = 1;
+class Missions
+{
+    class Mission1
+    {
+        template = "Antistasi.Altis";
+        difficulty = "Veteran";
+        class Params
+        {
+            MaxTime = 3600;
+            Sides[] = {{1, 2}, {3}};
+        };
+    };
+    class Mission2
+    {
+        template = "Escape.Tanoa"; // same difficulty as the first
+        difficulty = "Veteran";
+    };
+};
+"#;
+
+    #[test]
+    fn parses_a_written_config() {
+        let cfg = ServerCfg::parse(SERVER_CFG).unwrap();
+
+        assert_eq!(cfg.hostname, "The \"Best\" Server");
+        assert_eq!(cfg.password, "secret");
+        assert_eq!(cfg.max_players, 64);
+        assert_eq!(cfg.motd, ["Welcome", "Have fun, and \"behave\""]);
+        assert_eq!(cfg.headless_client_ips, ["127.0.0.1", "10.0.0.2"]);
+        assert!(cfg.trusts_localhost());
+        assert_eq!(
+            cfg.extra,
+            ["allowedFilePatching = 1;", "kickDuplicate = 1;"]
+        );
+
+        assert_eq!(cfg.difficulty, "Veteran");
+        assert_eq!(cfg.missions.len(), 2);
+        assert_eq!(cfg.missions[0].template, "Antistasi.Altis");
+        assert_eq!(cfg.missions[0].difficulty, None);
+        assert_eq!(cfg.missions[1].template, "Escape.Tanoa");
+
+        let params = &cfg.missions[0].extra[0];
+        assert!(params.starts_with("class Params\n{\n"));
+        assert!(params.contains("MaxTime = 3600;"));
+        assert!(params.contains("Sides[] = {{1, 2}, {3}};"));
+    }
+
+    #[test]
+    fn round_trips_through_to_cfg() {
+        let cfg = ServerCfg::parse(SERVER_CFG).unwrap();
+        let written = cfg.to_cfg();
+
+        let reread = ServerCfg::parse(&written).unwrap();
+
+        assert_eq!(reread.to_cfg(), written);
+        assert_eq!(reread.hostname, cfg.hostname);
+        assert_eq!(reread.motd, cfg.motd);
+        assert_eq!(reread.missions, cfg.missions);
+        assert_eq!(reread.extra, cfg.extra);
+    }
+
+    #[test]
+    fn round_trips_the_defaults() {
+        let cfg = ServerCfg::default();
+        let reread = ServerCfg::parse(&cfg.to_cfg()).unwrap();
+
+        assert_eq!(reread.to_cfg(), cfg.to_cfg());
+        assert!(reread.trusts_localhost());
+
+        let basic = BasicCfg::default();
+        let reread = BasicCfg::parse(&basic.to_cfg()).unwrap();
+
+        assert_eq!(reread.to_cfg(), basic.to_cfg());
+        assert!(reread.extra.is_empty());
+    }
+
+    #[test]
+    fn rejects_broken_syntax() {
+        for text in [
+            "hostname = \"unterminated;",
+            "motd[] = {\"a\", \"b\";",
+            "class Missions { class Mission1 { template = \"a\"; };",
+            "= 1;",
+            "}",
+        ] {
+            assert!(ServerCfg::parse(text).is_err(), "{}", text);
+        }
+    }
+}
@@ -0,0 +1,419 @@
+use std::{
+    fs,
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+};
+
+use num_bigint::BigUint;
+use sha1::{Digest, Sha1};
+
+use crate::errors::{AppError, AppResult};
+
+use super::{keys, pbo::Pbo};
+
+// Keys are 1024 to 4096 bits long in practice, anything far beyond is a broken file
+const MAX_KEY_LENGTH: usize = 2048;
+// PKCS#1 padding of a SHA1 hash needs room for the hash, its DigestInfo and 3 bytes
+const MIN_KEY_LENGTH: usize = 20 + 16 + 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureStatus {
+    Signed,
+    // Number of PBOs without a .bisign
+    Unsigned(usize),
+    // The mod ships .bisign files but no .bikey
+    NoKeys,
+    // A .bisign was made by an authority none of the mod's keys belong to
+    UnknownAuthority(String),
+    // The signature of this PBO does not verify against the key
+    Invalid(String),
+}
+
+impl SignatureStatus {
+    pub fn label(&self) -> String {
+        match self {
+            SignatureStatus::Signed => "signed".to_string(),
+            SignatureStatus::Unsigned(count) => format!("{} unsigned", count),
+            SignatureStatus::NoKeys => "no key".to_string(),
+            SignatureStatus::UnknownAuthority(authority) => format!("unknown key {}", authority),
+            SignatureStatus::Invalid(pbo) => format!("bad signature {}", pbo),
+        }
+    }
+}
+
+// An RSA public key as stored in .bikey files and embedded in .bisign files
+#[derive(Debug, Clone)]
+struct PublicKey {
+    authority: String,
+    exponent: BigUint,
+    modulus: BigUint,
+    // Key length in bytes
+    length: usize,
+}
+
+impl PublicKey {
+    fn read<R: BufRead>(reader: &mut R, path: &Path) -> AppResult<PublicKey> {
+        let invalid = || AppError::InvalidSignature(path.to_string_lossy().to_string());
+
+        let mut authority = Vec::new();
+        reader.read_until(0, &mut authority)?;
+        if authority.pop() != Some(0) {
+            return Err(invalid());
+        }
+
+        // Length of the blob, followed by the BLOBHEADER and "RSA1"
+        read_u32(reader)?;
+        let mut header = [0; 12];
+        reader.read_exact(&mut header)?;
+        if &header[8..12] != b"RSA1" {
+            return Err(invalid());
+        }
+
+        let bits = read_u32(reader)? as usize;
+        if bits / 8 < MIN_KEY_LENGTH {
+            return Err(invalid());
+        }
+
+        let exponent = read_u32(reader)?;
+        let modulus = read_le_bytes(reader, bits / 8, MAX_KEY_LENGTH, path)?;
+
+        // Verifying against a zero modulus would panic
+        if modulus.bits() == 0 {
+            return Err(invalid());
+        }
+
+        Ok(PublicKey {
+            authority: String::from_utf8_lossy(&authority).to_string(),
+            exponent: BigUint::from(exponent),
+            modulus,
+            length: bits / 8,
+        })
+    }
+
+    fn read_bikey(path: &Path) -> AppResult<PublicKey> {
+        PublicKey::read(&mut BufReader::new(fs::File::open(path)?), path)
+    }
+}
+
+#[derive(Debug)]
+struct BiSign {
+    key: PublicKey,
+    version: u32,
+    signatures: [BigUint; 3],
+}
+
+impl BiSign {
+    fn read(path: &Path) -> AppResult<BiSign> {
+        let mut reader = BufReader::new(fs::File::open(path)?);
+        let key = PublicKey::read(&mut reader, path)?;
+
+        // A signature is never longer than the key it was made with
+        let max = key.length;
+
+        let len = read_u32(&mut reader)? as usize;
+        let sig1 = read_le_bytes(&mut reader, len, max, path)?;
+        let version = read_u32(&mut reader)?;
+        let len = read_u32(&mut reader)? as usize;
+        let sig2 = read_le_bytes(&mut reader, len, max, path)?;
+        let len = read_u32(&mut reader)? as usize;
+        let sig3 = read_le_bytes(&mut reader, len, max, path)?;
+
+        Ok(BiSign {
+            key,
+            version,
+            signatures: [sig1, sig2, sig3],
+        })
+    }
+
+    // Checks the three signatures against the hashes of the PBO
+    fn verify(&self, pbo: &Pbo, key: &PublicKey) -> AppResult<bool> {
+        let Some(checksum) = pbo.stored_checksum()? else {
+            return Ok(false);
+        };
+
+        // Same as the integrity check, a pbo that doesn't match its own checksum is damaged
+        if checksum != pbo.computed_checksum()? {
+            return Ok(false);
+        }
+
+        let hashes = pbo_hashes(pbo, &checksum, self.version)?;
+
+        Ok(self.signatures.iter().zip(hashes).all(|(signature, hash)| {
+            signature.modpow(&key.exponent, &key.modulus) == pad_hash(&hash, key.length)
+        }))
+    }
+}
+
+// Checks every PBO of the mod for a .bisign from one of the mod's keys
+//
+// With deep set the signatures are also verified, which reads every PBO in full
+pub fn check_mod(mod_path: &Path, deep: bool) -> SignatureStatus {
+    let keys = keys::find_keys(mod_path)
+        .iter()
+        .filter_map(|path| PublicKey::read_bikey(path).ok())
+        .collect::<Vec<_>>();
+
+    let mut unsigned = 0;
+    let mut status = SignatureStatus::Signed;
+
+    for pbo_path in super::pbo::find_pbos(mod_path) {
+        let signs = find_bisigns(&pbo_path);
+
+        if signs.is_empty() {
+            unsigned += 1;
+            continue;
+        }
+
+        if status != SignatureStatus::Signed {
+            continue;
+        }
+
+        let parsed = signs
+            .iter()
+            .filter_map(|path| BiSign::read(path).ok())
+            .collect::<Vec<_>>();
+        let unreadable = parsed.len() < signs.len();
+        let signs = parsed;
+
+        let matching = signs.iter().find_map(|sign| {
+            keys.iter()
+                .find(|key| key.authority == sign.key.authority)
+                .map(|key| (sign, key))
+        });
+
+        let Some((sign, key)) = matching else {
+            let name = pbo_path.file_name().unwrap_or_default();
+
+            // The .bisign the server would check may well be the one that can't be read
+            status = if unreadable {
+                SignatureStatus::Invalid(name.to_string_lossy().to_string())
+            } else if keys.is_empty() {
+                SignatureStatus::NoKeys
+            } else {
+                SignatureStatus::UnknownAuthority(
+                    signs
+                        .first()
+                        .map(|s| s.key.authority.clone())
+                        .unwrap_or_default(),
+                )
+            };
+            continue;
+        };
+
+        if deep {
+            let verified = Pbo::read(&pbo_path)
+                .and_then(|pbo| sign.verify(&pbo, key))
+                .unwrap_or(false);
+
+            if !verified {
+                let name = pbo_path.file_name().unwrap_or_default();
+                status = SignatureStatus::Invalid(name.to_string_lossy().to_string());
+            }
+        }
+    }
+
+    if unsigned > 0 {
+        return SignatureStatus::Unsigned(unsigned);
+    }
+
+    status
+}
+
+// Verifies the signatures of mods on a background thread, since reading every PBO can
+// take minutes for large modsets
+pub struct Verification {
+    receiver: Receiver<(u64, SignatureStatus)>,
+    pub total: usize,
+    pub done: usize,
+}
+
+impl Verification {
+    pub fn start(mods: Vec<(u64, PathBuf)>) -> Verification {
+        let (sender, receiver) = mpsc::channel();
+        let total = mods.len();
+
+        thread::spawn(move || {
+            for (id, mod_path) in mods {
+                // Nobody is waiting for the results anymore
+                if sender.send((id, check_mod(&mod_path, true))).is_err() {
+                    return;
+                }
+            }
+        });
+
+        Verification {
+            receiver,
+            total,
+            done: 0,
+        }
+    }
+
+    // The results that came in since the last poll
+    pub fn poll(&mut self) -> Vec<(u64, SignatureStatus)> {
+        let mut results = Vec::new();
+
+        loop {
+            match self.receiver.try_recv() {
+                Ok(result) => results.push(result),
+                Err(TryRecvError::Empty) => break,
+                // The thread is done, or gave up on a mod it couldn't read
+                Err(TryRecvError::Disconnected) => {
+                    self.done = self.total;
+                    return results;
+                }
+            }
+        }
+
+        self.done += results.len();
+
+        results
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.done >= self.total
+    }
+}
+
+// <name>.pbo.<authority>.bisign next to the PBO
+fn find_bisigns(pbo_path: &Path) -> Vec<PathBuf> {
+    let (Some(dir), Some(pbo_name)) = (pbo_path.parent(), pbo_path.file_name()) else {
+        return Vec::new();
+    };
+    let prefix = format!("{}.", pbo_name.to_string_lossy().to_lowercase());
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            let name = p
+                .file_name()
+                .map(|n| n.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            name.starts_with(&prefix) && name.ends_with(".bisign")
+        })
+        .collect()
+}
+
+// The three hashes BI signatures are made over
+//
+// 1. The PBO checksum
+// 2. The checksum, the hash of the file names and the prefix
+// 3. The hash of the file contents, the hash of the file names and the prefix
+fn pbo_hashes(pbo: &Pbo, checksum: &[u8; 20], version: u32) -> AppResult<[Vec<u8>; 3]> {
+    let mut entries = pbo.entries.iter().collect::<Vec<_>>();
+    entries.sort_by_key(|e| e.name.to_lowercase());
+
+    let mut name_hasher = Sha1::new();
+    for entry in entries.iter().filter(|e| e.data_size > 0) {
+        name_hasher.update(entry.name.to_lowercase().as_bytes());
+    }
+    let name_hash = name_hasher.finalize();
+
+    let mut file_hasher = Sha1::new();
+    let mut hashed_any = false;
+    for entry in &entries {
+        let name = entry.name.to_lowercase();
+        let extension = name.rsplit('.').next().unwrap_or_default();
+
+        if !file_hash_includes(extension, version) {
+            continue;
+        }
+
+        file_hasher.update(pbo.read_entry(entry)?);
+        hashed_any = true;
+    }
+    if !hashed_any {
+        file_hasher.update(if version == 2 { b"nothing" } else { b"gnihton" });
+    }
+    let file_hash = file_hasher.finalize();
+
+    let prefix = match pbo.prefix() {
+        Some(prefix) if !prefix.ends_with('\\') => format!("{}\\", prefix),
+        Some(prefix) => prefix.to_string(),
+        None => String::new(),
+    };
+
+    let mut hasher = Sha1::new();
+    hasher.update(checksum);
+    hasher.update(name_hash);
+    hasher.update(prefix.as_bytes());
+    let hash2 = hasher.finalize();
+
+    let mut hasher = Sha1::new();
+    hasher.update(file_hash);
+    hasher.update(name_hash);
+    hasher.update(prefix.as_bytes());
+    let hash3 = hasher.finalize();
+
+    Ok([checksum.to_vec(), hash2.to_vec(), hash3.to_vec()])
+}
+
+// Version 2 hashes everything but binary assets, version 3 only scripts and configs
+fn file_hash_includes(extension: &str, version: u32) -> bool {
+    if version == 2 {
+        !matches!(
+            extension,
+            "paa"
+                | "jpg"
+                | "p3d"
+                | "tga"
+                | "rvmat"
+                | "lip"
+                | "ogg"
+                | "wss"
+                | "png"
+                | "rtm"
+                | "pac"
+                | "fxy"
+                | "wrp"
+        )
+    } else {
+        matches!(
+            extension,
+            "sqf" | "inc" | "bikb" | "ext" | "fsm" | "sqm" | "hpp" | "cfg" | "sqs" | "h"
+        )
+    }
+}
+
+// PKCS#1 v1.5 padding of a SHA1 hash to the key length
+fn pad_hash(hash: &[u8], length: usize) -> BigUint {
+    let mut padded = vec![0x00, 0x01];
+    padded.resize(length.saturating_sub(hash.len() + 16), 0xff);
+    padded.extend([
+        0x00, 0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04,
+        0x14,
+    ]);
+    padded.extend(hash);
+
+    BigUint::from_bytes_be(&padded)
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> AppResult<u32> {
+    let mut buf = [0; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+// The length comes from the file, so it is checked before anything is allocated
+fn read_le_bytes<R: Read>(
+    reader: &mut R,
+    len: usize,
+    max: usize,
+    path: &Path,
+) -> AppResult<BigUint> {
+    if len > max {
+        return Err(AppError::InvalidSignature(
+            path.to_string_lossy().to_string(),
+        ));
+    }
+
+    let mut buf = vec![0; len];
+    reader.read_exact(&mut buf)?;
+    Ok(BigUint::from_bytes_le(&buf))
+}
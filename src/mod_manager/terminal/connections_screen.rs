@@ -0,0 +1,407 @@
+use std::io::{Stdout, Write};
+
+use crossterm::{
+    cursor::{self, SetCursorStyle},
+    event::{self, Event, KeyCode, KeyEvent},
+    execute,
+    style::{Print, PrintStyledContent},
+};
+
+use crate::{
+    errors::AppResult,
+    mod_manager::{
+        connection::{self, Connection, ConnectionField, Passwords},
+        utils,
+    },
+};
+
+use super::{super::theme::Role, Terminal};
+
+impl Terminal<'_> {
+    pub(super) fn connections_screen(&mut self, stdout: &mut Stdout) -> AppResult<()> {
+        let mut passwords = Passwords::read();
+        let mut selected_index = 0;
+        let mut message: Option<(Role, String)> = None;
+
+        loop {
+            self.render_connections_screen(stdout, &passwords, selected_index, &message)?;
+
+            let Event::Key(KeyEvent { code, .. }) = event::read()? else {
+                continue;
+            };
+
+            let connections_len = self.mod_manager.config.get_connections().len();
+
+            match code {
+                KeyCode::Char('k') | KeyCode::Char('w') | KeyCode::Up => {
+                    selected_index = selected_index.saturating_sub(1);
+                }
+                KeyCode::Char('j') | KeyCode::Char('s') | KeyCode::Down
+                    if selected_index + 1 < connections_len =>
+                {
+                    selected_index += 1;
+                }
+                KeyCode::Char('n') => {
+                    let connections = self.mod_manager.config.get_connections();
+                    let taken = connections
+                        .iter()
+                        .map(|c| c.name.as_str())
+                        .collect::<Vec<_>>();
+                    let name = utils::unused_name("Server", &taken);
+
+                    self.mod_manager
+                        .config
+                        .get_connections_mut()
+                        .push(Connection::new(name));
+                    self.mod_manager.config.save()?;
+
+                    selected_index = connections_len;
+                    self.connection_form_screen(stdout, &mut passwords, selected_index)?;
+                }
+                KeyCode::Delete if selected_index < connections_len => {
+                    let connection = self
+                        .mod_manager
+                        .config
+                        .get_connections_mut()
+                        .remove(selected_index);
+                    self.mod_manager.config.save()?;
+
+                    passwords.remove(&connection.name);
+                    passwords.save()?;
+
+                    selected_index = selected_index.saturating_sub(1);
+                }
+                KeyCode::Char('e') if selected_index < connections_len => {
+                    self.connection_form_screen(stdout, &mut passwords, selected_index)?;
+                }
+                KeyCode::Char('i') if selected_index < connections_len => {
+                    self.query_screen(stdout, selected_index)?;
+                }
+                KeyCode::Char('m') if selected_index < connections_len => {
+                    // The server's modset is whatever is enabled in the mod list right now
+                    let enabled = self
+                        .mod_manager
+                        .loaded_mods
+                        .filter(|m| m.enabled)
+                        .iter()
+                        .map(|m| m.id)
+                        .collect::<Vec<_>>();
+
+                    message = Some((
+                        Role::Success,
+                        format!("Saved {} enabled mods for this server", enabled.len()),
+                    ));
+
+                    self.mod_manager.config.get_connections_mut()[selected_index].mods = enabled;
+                    self.mod_manager.config.save()?;
+                }
+                KeyCode::Enter if selected_index < connections_len => {
+                    match self.join_server(selected_index, &passwords) {
+                        Ok(missing) if missing.is_empty() => break,
+                        Ok(missing) => {
+                            // The game is started anyway, the server will tell what is missing
+                            self.status = Some((
+                                Role::Warning,
+                                format!(
+                                    "Not installed: {}",
+                                    missing
+                                        .iter()
+                                        .map(|id| id.to_string())
+                                        .collect::<Vec<_>>()
+                                        .join(", ")
+                                ),
+                            ));
+                            break;
+                        }
+                        Err(e) => message = Some((Role::Error, e.to_string())),
+                    }
+                }
+                KeyCode::Esc | KeyCode::Char('q') => break,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    // Enables exactly the server's mods and launches the game connecting to it
+    //
+    // Returns the required mods that are not installed
+    fn join_server(&mut self, index: usize, passwords: &Passwords) -> AppResult<Vec<u64>> {
+        let connection = self.mod_manager.config.get_connections()[index].clone();
+
+        for m in self.mod_manager.loaded_mods.all_items_mut() {
+            m.enabled = connection.mods.contains(&m.id);
+        }
+
+        let missing = connection
+            .mods
+            .iter()
+            .filter(|id| self.mod_manager.find_mod(**id).is_none())
+            .copied()
+            .collect::<Vec<_>>();
+
+        self.launch_game(connection.args(passwords.get(&connection.name)))?;
+
+        Ok(missing)
+    }
+
+    fn connection_form_screen(
+        &mut self,
+        stdout: &mut Stdout,
+        passwords: &mut Passwords,
+        index: usize,
+    ) -> AppResult<()> {
+        let mut selected_index = 0;
+        let mut editing: Option<String> = None;
+        let mut message: Option<(Role, String)> = None;
+
+        loop {
+            self.render_connection_form_screen(
+                stdout,
+                passwords,
+                index,
+                selected_index,
+                editing.as_deref(),
+                &message,
+            )?;
+
+            let Event::Key(KeyEvent { code, .. }) = event::read()? else {
+                continue;
+            };
+
+            let field = ConnectionField::ALL[selected_index];
+            let taken = self
+                .mod_manager
+                .config
+                .get_connections()
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != index)
+                .map(|(_, c)| c.name.clone())
+                .collect::<Vec<_>>();
+            let connection = &mut self.mod_manager.config.get_connections_mut()[index];
+
+            if let Some(value) = &mut editing {
+                match code {
+                    KeyCode::Enter => {
+                        let result =
+                            connection::set_field(connection, passwords, &taken, field, value);
+
+                        message = match result {
+                            Ok(()) => None,
+                            Err(e) => Some((Role::Error, e.to_string())),
+                        };
+                        editing = None;
+                    }
+                    KeyCode::Esc => editing = None,
+                    KeyCode::Backspace => {
+                        value.pop();
+                    }
+                    KeyCode::Char(c) => value.push(c),
+                    _ => {}
+                }
+
+                if editing.is_none() {
+                    execute!(stdout, cursor::Hide, SetCursorStyle::DefaultUserShape)?;
+                }
+                continue;
+            }
+
+            match code {
+                KeyCode::Char('k') | KeyCode::Char('w') | KeyCode::Up => {
+                    selected_index = selected_index.saturating_sub(1);
+                }
+                KeyCode::Char('j') | KeyCode::Char('s') | KeyCode::Down
+                    if selected_index + 1 < ConnectionField::ALL.len() =>
+                {
+                    selected_index += 1;
+                }
+                KeyCode::Enter => {
+                    editing = Some(connection::get_field(connection, passwords, field));
+                    execute!(stdout, cursor::Show, SetCursorStyle::BlinkingUnderScore)?;
+                }
+                KeyCode::Esc | KeyCode::Char('q') => break,
+                _ => {}
+            }
+        }
+
+        self.mod_manager.config.save()?;
+        passwords.save()?;
+
+        Ok(())
+    }
+
+    fn render_connections_screen(
+        &self,
+        stdout: &mut Stdout,
+        passwords: &Passwords,
+        selected_index: usize,
+        message: &Option<(Role, String)>,
+    ) -> AppResult<()> {
+        self.clear_screen(stdout)?;
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 0),
+            PrintStyledContent(self.theme.paint(Role::Title, "Arma 3 Mod Manager CLI")),
+        )?;
+
+        if let Some((role, text)) = message {
+            execute!(
+                stdout,
+                cursor::MoveTo(0, 1),
+                PrintStyledContent(self.theme.paint(*role, text)),
+            )?;
+        }
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 2),
+            Print(
+                "<ENTER> Join  <I> Query  <E> Edit  <M> Use Enabled Mods  <N> New  <DEL> Delete  <ESC> Back"
+            ),
+        )?;
+
+        let connections = self.mod_manager.config.get_connections();
+
+        if connections.is_empty() {
+            execute!(
+                stdout,
+                cursor::MoveTo(3, 4),
+                Print("No saved servers yet, press <N> to add one"),
+            )?;
+        }
+
+        let mut top_offset = 4;
+
+        for (i, connection) in connections.iter().enumerate() {
+            let cursor = if i == selected_index { " > " } else { "   " };
+
+            let missing = connection
+                .mods
+                .iter()
+                .filter(|id| self.mod_manager.find_mod(**id).is_none())
+                .count();
+
+            // Only show whether a password is set, never the password itself
+            let password = passwords.get(&connection.name).map(|_| "********");
+
+            execute!(
+                stdout,
+                cursor::MoveTo(0, top_offset),
+                PrintStyledContent(self.theme.paint(Role::Cursor, cursor)),
+                cursor::MoveTo(3, top_offset),
+                Print(&format!(
+                    "{:<30}{}:{}",
+                    connection.name, connection.host, connection.port
+                )),
+                cursor::MoveTo(5, top_offset + 1),
+                PrintStyledContent(self.theme.paint(
+                    if missing > 0 {
+                        Role::Warning
+                    } else {
+                        Role::Muted
+                    },
+                    &format!(
+                        "Mods: {} ({} not installed)  {}",
+                        connection.mods.len(),
+                        missing,
+                        connection.args(password).join(" ")
+                    )
+                )),
+            )?;
+
+            top_offset += 2;
+        }
+
+        stdout.flush()?;
+
+        Ok(())
+    }
+
+    fn render_connection_form_screen(
+        &self,
+        stdout: &mut Stdout,
+        passwords: &Passwords,
+        index: usize,
+        selected_index: usize,
+        editing: Option<&str>,
+        message: &Option<(Role, String)>,
+    ) -> AppResult<()> {
+        self.clear_screen(stdout)?;
+
+        let connection = &self.mod_manager.config.get_connections()[index];
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 0),
+            PrintStyledContent(self.theme.paint(
+                Role::Title,
+                &format!("Arma 3 Mod Manager CLI - {}", connection.name)
+            )),
+        )?;
+
+        if let Some((role, message)) = message {
+            execute!(
+                stdout,
+                cursor::MoveTo(0, 1),
+                PrintStyledContent(self.theme.paint(*role, message)),
+            )?;
+        }
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 2),
+            Print("<ENTER> Edit  <ESC> Back"),
+        )?;
+
+        let label_padding = 12;
+        let top_offset = 4;
+        let mut cursor_pos = None;
+
+        for (i, field) in ConnectionField::ALL.iter().enumerate() {
+            let y = top_offset + i as u16;
+            let cursor = if i == selected_index { " > " } else { "   " };
+
+            let value = connection::get_field(connection, passwords, *field);
+            let value = match editing {
+                Some(value) if i == selected_index => {
+                    cursor_pos = Some((3 + label_padding + value.chars().count()) as u16);
+
+                    if *field == ConnectionField::Password {
+                        "*".repeat(value.chars().count())
+                    } else {
+                        value.to_string()
+                    }
+                }
+                _ if *field == ConnectionField::Password => "*".repeat(value.chars().count()),
+                _ => value,
+            };
+
+            execute!(
+                stdout,
+                cursor::MoveTo(0, y),
+                PrintStyledContent(self.theme.paint(Role::Cursor, cursor)),
+                cursor::MoveTo(3, y),
+                Print(&format!(
+                    "{:<padding$}{}",
+                    field.label(),
+                    value,
+                    padding = label_padding
+                )),
+            )?;
+        }
+
+        if let Some(x) = cursor_pos {
+            execute!(
+                stdout,
+                cursor::MoveTo(x, top_offset + selected_index as u16)
+            )?;
+        }
+
+        stdout.flush()?;
+
+        Ok(())
+    }
+}
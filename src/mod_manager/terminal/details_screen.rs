@@ -0,0 +1,220 @@
+use std::io::{Stdout, Write};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent},
+    execute,
+    style::{Print, PrintStyledContent},
+};
+
+use crate::{
+    errors::AppResult,
+    mod_manager::{integrity, signatures::SignatureStatus},
+};
+
+use super::{super::theme::Role, Terminal};
+
+impl Terminal<'_> {
+    // Shows the details of a mod with its signature and integrity checks
+    pub(super) fn details_screen(&mut self, stdout: &mut Stdout, id: u64) -> AppResult<()> {
+        let mut message: Option<(Role, String)> = None;
+        // Marking rewrites Steam's manifest, so it has to be pressed twice
+        let mut confirm_mark = false;
+
+        loop {
+            self.render_details_screen(stdout, id, &message)?;
+
+            let Event::Key(KeyEvent { code, .. }) = event::read()? else {
+                continue;
+            };
+
+            let workshop_path = self.mod_manager.config.get_workshop_path().to_path_buf();
+
+            match code {
+                KeyCode::Char('i') => {
+                    message = Some((Role::Title, "Checking...".to_string()));
+                    self.render_details_screen(stdout, id, &message)?;
+
+                    message = match integrity::check_mod(&workshop_path, id) {
+                        Ok(report) => {
+                            self.integrity.insert(id, report);
+                            None
+                        }
+                        Err(e) => Some((Role::Error, e.to_string())),
+                    };
+                }
+                KeyCode::Char('m') if confirm_mark => {
+                    message = Some(
+                        match integrity::mark_for_reverification(&workshop_path, id) {
+                            Ok(()) => (
+                                Role::Success,
+                                "Marked, Steam downloads the mod again when it is restarted"
+                                    .to_string(),
+                            ),
+                            Err(e) => (Role::Error, e.to_string()),
+                        },
+                    );
+                }
+                KeyCode::Char('m') => {
+                    message = Some((
+                        Role::Warning,
+                        "Close Steam first, then press <M> again to mark the mod".to_string(),
+                    ));
+                    confirm_mark = true;
+                    continue;
+                }
+                KeyCode::Esc | KeyCode::Char('q') => break,
+                _ => {}
+            }
+
+            confirm_mark = false;
+        }
+
+        Ok(())
+    }
+
+    fn render_details_screen(
+        &self,
+        stdout: &mut Stdout,
+        id: u64,
+        message: &Option<(Role, String)>,
+    ) -> AppResult<()> {
+        self.clear_screen(stdout)?;
+
+        let Some(m) = self.mod_manager.find_mod(id) else {
+            return Ok(());
+        };
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 0),
+            PrintStyledContent(
+                self.theme
+                    .paint(Role::Title, &format!("Arma 3 Mod Manager CLI - {}", m.name))
+            ),
+        )?;
+
+        if let Some((role, text)) = message {
+            execute!(
+                stdout,
+                cursor::MoveTo(0, 1),
+                PrintStyledContent(self.theme.paint(*role, text)),
+            )?;
+        }
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 2),
+            Print("<I> Check Integrity  <M> Mark for Re-download in Steam  <ESC> Back"),
+        )?;
+
+        let signature = match &m.signature {
+            Some(status) => status.label(),
+            None => "not checked".to_string(),
+        };
+
+        let details = [
+            ("ID", m.id.to_string()),
+            (
+                "Path",
+                m.get_path(self.mod_manager.config.get_workshop_path())
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+            ("Enabled", if m.enabled { "yes" } else { "no" }.to_string()),
+            ("Signature", signature),
+            ("Tags", self.mod_manager.config.get_tags(m.id).join(", ")),
+        ];
+
+        let mut top_offset = 4;
+
+        for (label, value) in details {
+            execute!(
+                stdout,
+                cursor::MoveTo(3, top_offset),
+                Print(&format!("{:<14}{}", label, value)),
+            )?;
+            top_offset += 1;
+        }
+
+        if let Some(SignatureStatus::Invalid(_)) = &m.signature {
+            execute!(
+                stdout,
+                cursor::MoveTo(3, top_offset),
+                PrintStyledContent(self.theme.paint(
+                    Role::Error,
+                    "A signature doesn't verify, the mod may be corrupt or modified"
+                )),
+            )?;
+            top_offset += 1;
+        }
+
+        top_offset += 1;
+
+        let Some(report) = self.integrity.get(&id) else {
+            execute!(
+                stdout,
+                cursor::MoveTo(3, top_offset),
+                PrintStyledContent(
+                    self.theme
+                        .paint(Role::Muted, "Integrity not checked yet, press <I>")
+                ),
+            )?;
+            stdout.flush()?;
+            return Ok(());
+        };
+
+        let manifest_size = match report.manifest_size {
+            Some(size) => size.to_string(),
+            None => "unknown".to_string(),
+        };
+
+        execute!(
+            stdout,
+            cursor::MoveTo(3, top_offset),
+            PrintStyledContent(self.theme.paint(
+                if report.size_matches() {
+                    Role::Success
+                } else {
+                    Role::Error
+                },
+                &format!(
+                    "{:<14}{} bytes (Steam: {})",
+                    "Size", report.size_on_disk, manifest_size
+                )
+            )),
+            cursor::MoveTo(3, top_offset + 1),
+            PrintStyledContent(self.theme.paint(
+                if report.problems.is_empty() {
+                    Role::Success
+                } else {
+                    Role::Error
+                },
+                &format!(
+                    "{:<14}{} checked, {} damaged",
+                    "PBOs",
+                    report.pbos_checked,
+                    report.problems.len()
+                )
+            )),
+        )?;
+
+        top_offset += 3;
+
+        for (name, problem) in &report.problems {
+            execute!(
+                stdout,
+                cursor::MoveTo(5, top_offset),
+                PrintStyledContent(
+                    self.theme
+                        .paint(Role::Error, &format!("{:<40}{}", name, problem.label()))
+                ),
+            )?;
+            top_offset += 1;
+        }
+
+        stdout.flush()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use std::{
+    io, panic,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+};
+
+use crossterm::{
+    cursor,
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute, terminal,
+};
+
+use crate::errors::AppResult;
+
+// Puts the terminal into raw mode on the alternate screen, capturing the mouse if asked to,
+// and restores it when dropped, also when the app returns early with an error or panics
+pub struct TerminalGuard;
+
+// Whether the terminal still has to be restored, a panic restores it before the guard drops
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+impl TerminalGuard {
+    pub fn enter(mouse: bool) -> AppResult<TerminalGuard> {
+        let mut stdout = io::stdout();
+        let guard = TerminalGuard;
+
+        // The panic message would be lost on the alternate screen, restore before printing it.
+        // Only a panic on the main thread ends the app, background workers report through
+        // their channels being disconnected and the interface keeps running
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if thread::current().name() == Some("main") {
+                restore();
+            }
+            previous_hook(info);
+        }));
+
+        // Set first, so whatever part of the setup succeeded is undone if the rest fails
+        ACTIVE.store(true, Ordering::SeqCst);
+
+        execute!(stdout, cursor::SavePosition)?;
+        execute!(stdout, terminal::EnterAlternateScreen)?;
+
+        execute!(stdout, cursor::Hide)?;
+
+        terminal::enable_raw_mode()?;
+
+        if mouse {
+            execute!(stdout, EnableMouseCapture)?;
+        }
+
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+// Best effort, there is nothing left to report a failure to
+fn restore() {
+    if !ACTIVE.swap(false, Ordering::SeqCst) {
+        return;
+    }
+
+    let mut stdout = io::stdout();
+
+    // Harmless when the mouse wasn't captured
+    let _ = execute!(stdout, DisableMouseCapture);
+    let _ = terminal::disable_raw_mode();
+
+    let _ = execute!(stdout, terminal::LeaveAlternateScreen);
+    let _ = execute!(stdout, cursor::RestorePosition);
+
+    let _ = execute!(stdout, cursor::Show);
+}
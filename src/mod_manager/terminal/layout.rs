@@ -0,0 +1,116 @@
+// Where the parts of the mod list screen go for a given terminal size
+
+use super::{super::keymap::HelpEntry, text};
+
+// Title, status line, mod count and a blank line
+pub(super) const LIST_TOP: u16 = 4;
+// The page arrows and the signature of the selected mod
+const BELOW_LIST: u16 = 2;
+
+// Cursor, "[X] " and the " NEW" marker around the name
+const CURSOR_WIDTH: usize = 3;
+const CHECKBOX_WIDTH: usize = 4;
+const MARKER_WIDTH: usize = 4;
+const SCROLLBAR_WIDTH: usize = 2;
+const MIN_NAME_WIDTH: usize = 10;
+
+pub(super) const HELP_PADDING: usize = 27;
+const HELP_GAP: u16 = 2;
+// Below this the help panel would squeeze the names, so it moves below the list
+const MIN_LIST_WIDTH: u16 = 48;
+
+pub(super) enum HelpPlacement {
+    // Panel right of the list, starting at the column
+    Side(u16),
+    // Wrapped lines at the bottom of the screen, with the indices of the entries on each
+    Footer(Vec<Vec<usize>>),
+}
+
+pub(super) struct Layout {
+    pub height: u16,
+    pub page_size: usize,
+    pub name_width: usize,
+    pub help: HelpPlacement,
+}
+
+impl Layout {
+    pub fn new(width: u16, height: u16, entries: &[HelpEntry]) -> Layout {
+        // Action column plus the longest keybinding
+        let help_width = entries
+            .iter()
+            .map(|entry| HELP_PADDING + text::width(&entry.keybinding()))
+            .max()
+            .unwrap_or(0) as u16;
+
+        let (list_width, help) = if width >= MIN_LIST_WIDTH + HELP_GAP + help_width {
+            let list_width = width - help_width - HELP_GAP;
+            (list_width, HelpPlacement::Side(list_width + HELP_GAP))
+        } else {
+            let lines = footer_lines(width as usize, entries);
+            (width, HelpPlacement::Footer(lines))
+        };
+
+        let footer_height = match &help {
+            HelpPlacement::Footer(lines) => lines.len() as u16,
+            HelpPlacement::Side(_) => 0,
+        };
+
+        let page_size = height.saturating_sub(LIST_TOP + BELOW_LIST + footer_height);
+
+        let name_width = (list_width as usize)
+            .saturating_sub(CURSOR_WIDTH + CHECKBOX_WIDTH + MARKER_WIDTH + SCROLLBAR_WIDTH)
+            .max(MIN_NAME_WIDTH);
+
+        Layout {
+            height,
+            page_size: (page_size as usize).max(1),
+            name_width,
+            help,
+        }
+    }
+
+    // Column of the NEW/UPD marker, right after the name
+    pub fn marker_column(&self) -> u16 {
+        (CURSOR_WIDTH + CHECKBOX_WIDTH + self.name_width + 1) as u16
+    }
+
+    pub fn scrollbar_column(&self) -> u16 {
+        self.marker_column() + MARKER_WIDTH as u16
+    }
+}
+
+pub(super) const FOOTER_SEPARATOR: &str = " | ";
+
+// A help entry as shown in the footer, keys first
+pub(super) fn footer_entry(entry: &HelpEntry) -> String {
+    format!("{} {}", entry.keybinding(), entry.text)
+}
+
+// The help entries packed into as few lines as fit the width
+fn footer_lines(width: usize, entries: &[HelpEntry]) -> Vec<Vec<usize>> {
+    let mut lines = Vec::new();
+    let mut line = Vec::new();
+    let mut line_width = 0;
+
+    for (i, entry) in entries.iter().enumerate() {
+        let entry_width = text::width(&footer_entry(entry));
+
+        if !line.is_empty() && line_width + FOOTER_SEPARATOR.len() + entry_width > width {
+            lines.push(std::mem::take(&mut line));
+            line_width = 0;
+        }
+
+        if !line.is_empty() {
+            line_width += FOOTER_SEPARATOR.len();
+        }
+
+        line.push(i);
+        line_width += entry_width;
+    }
+
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
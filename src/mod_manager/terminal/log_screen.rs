@@ -0,0 +1,313 @@
+use std::{
+    io::{Stdout, Write},
+    time::Duration,
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent},
+    execute,
+    style::{Print, PrintStyledContent},
+    terminal,
+};
+
+use crate::{
+    errors::AppResult,
+    mod_manager::{
+        diagnostics::{self, AddonIndex, IssueKind},
+        rpt::{self, RptTail, Severity},
+    },
+};
+
+use super::{super::theme::Role, Terminal};
+
+impl Terminal<'_> {
+    pub(super) fn log_screen(&mut self, stdout: &mut Stdout) -> AppResult<()> {
+        let config = &self.mod_manager.config;
+        let rpt_dirs = rpt::rpt_dirs(
+            config.get_game_path(),
+            config.get_default_args(),
+            config.get_rpt_path(),
+        );
+
+        let mut tail: Option<RptTail> = None;
+        let mut filter = String::new();
+        let mut editing_filter = false;
+        // Number of lines scrolled up from the bottom, 0 follows the log
+        let mut scroll: usize = 0;
+
+        loop {
+            // Switch to a newer log when the game is (re)launched
+            if let Some(newest) = rpt::find_newest(&rpt_dirs, self.launched_at) {
+                if tail.as_ref().is_none_or(|t| t.path() != newest) {
+                    tail = Some(RptTail::new(newest));
+                    scroll = 0;
+                }
+            }
+
+            if let Some(tail) = &mut tail {
+                tail.poll()?;
+            }
+
+            self.check_processes()?;
+            self.render_log_screen(stdout, tail.as_ref(), &filter, editing_filter, scroll)?;
+
+            if !event::poll(Duration::from_millis(500))? {
+                continue;
+            }
+
+            if let Event::Key(KeyEvent { code, .. }) = event::read()? {
+                if editing_filter {
+                    match code {
+                        KeyCode::Enter => editing_filter = false,
+                        KeyCode::Esc => {
+                            filter.clear();
+                            editing_filter = false;
+                        }
+                        KeyCode::Backspace => {
+                            filter.pop();
+                        }
+                        KeyCode::Char(c) => filter.push(c),
+                        _ => {}
+                    }
+                    scroll = 0;
+                    continue;
+                }
+
+                match code {
+                    KeyCode::Char('k') | KeyCode::Char('w') | KeyCode::Up => scroll += 1,
+                    KeyCode::Char('j') | KeyCode::Char('s') | KeyCode::Down => {
+                        scroll = scroll.saturating_sub(1)
+                    }
+                    KeyCode::PageUp => scroll += 10,
+                    KeyCode::PageDown => scroll = scroll.saturating_sub(10),
+                    KeyCode::End => scroll = 0,
+                    KeyCode::Char('/') => editing_filter = true,
+                    KeyCode::Char('m') => {
+                        if let Some(tail) = &tail {
+                            self.diagnostics_screen(stdout, tail.lines())?;
+                        }
+                    }
+                    KeyCode::Esc | KeyCode::Char('q') => break,
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn render_log_screen(
+        &self,
+        stdout: &mut Stdout,
+        tail: Option<&RptTail>,
+        filter: &str,
+        editing_filter: bool,
+        scroll: usize,
+    ) -> AppResult<()> {
+        self.clear_screen(stdout)?;
+
+        let (width, height) = terminal::size()?;
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 0),
+            PrintStyledContent(self.theme.paint(Role::Title, "Arma 3 Mod Manager CLI")),
+        )?;
+
+        let Some(tail) = tail else {
+            execute!(
+                stdout,
+                cursor::MoveTo(0, 2),
+                Print("No .rpt log found yet, waiting for the game to write one"),
+                cursor::MoveTo(0, 4),
+                Print("Press <ESC> to go back"),
+            )?;
+            stdout.flush()?;
+
+            return Ok(());
+        };
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 1),
+            Print(tail.path().to_string_lossy()),
+        )?;
+
+        let filter_text = if editing_filter {
+            format!("Filter: {}_", filter)
+        } else if !filter.is_empty() {
+            format!("Filter: {}", filter)
+        } else {
+            "Press </> to filter, <M> to find missing mods".to_string()
+        };
+
+        let follow_text = if scroll == 0 { "Following" } else { "Paused" };
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 2),
+            Print(&format!("{:<50}{}", filter_text, follow_text)),
+        )?;
+
+        let filter = filter.to_lowercase();
+        let lines = tail
+            .lines()
+            .iter()
+            .filter(|line| filter.is_empty() || line.to_lowercase().contains(&filter))
+            .collect::<Vec<_>>();
+
+        let top_offset = 4;
+        let visible = height.saturating_sub(top_offset) as usize;
+        let end = lines.len().saturating_sub(scroll.min(lines.len()));
+        let start = end.saturating_sub(visible);
+
+        for (i, line) in lines[start..end].iter().enumerate() {
+            let role = match Severity::of(line) {
+                Severity::Error => Role::Error,
+                Severity::Warning => Role::Warning,
+                Severity::Info => Role::Muted,
+            };
+
+            let line = line.chars().take(width as usize).collect::<String>();
+
+            execute!(
+                stdout,
+                cursor::MoveTo(0, top_offset + i as u16),
+                PrintStyledContent(self.theme.paint(role, line)),
+            )?;
+        }
+
+        stdout.flush()?;
+
+        Ok(())
+    }
+
+    fn diagnostics_screen(&mut self, stdout: &mut Stdout, lines: &[String]) -> AppResult<()> {
+        self.clear_screen(stdout)?;
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 0),
+            PrintStyledContent(self.theme.paint(Role::Title, "Arma 3 Mod Manager CLI")),
+            cursor::MoveTo(0, 2),
+            Print("Scanning installed mods..."),
+        )?;
+        stdout.flush()?;
+
+        let index = AddonIndex::build(
+            self.mod_manager.loaded_mods.all_items(),
+            self.mod_manager.config.get_workshop_path(),
+        );
+        let issues = diagnostics::diagnose(lines, &index);
+
+        // Marked in the mod list until the missing addon is enabled
+        self.conflicts = issues
+            .iter()
+            .filter_map(|issue| match &issue.kind {
+                IssueKind::RequiresAddon { needed_by, .. } => {
+                    needed_by.map(|id| (id, issue.provider))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let mut selected_index = 0;
+        // Index of the first issue on screen
+        let mut scroll = 0;
+
+        loop {
+            self.clear_screen(stdout)?;
+
+            execute!(
+                stdout,
+                cursor::MoveTo(0, 0),
+                PrintStyledContent(self.theme.paint(Role::Title, "Arma 3 Mod Manager CLI")),
+                cursor::MoveTo(0, 2),
+                Print(&format!(
+                    "Issues: {:<10}Press <ENTER> to enable the missing mod, <ESC> to go back",
+                    issues.len()
+                )),
+            )?;
+
+            let (_, height) = terminal::size()?;
+            let mut top_offset = 4;
+
+            // Each issue takes two lines
+            let visible = (height.saturating_sub(top_offset + 1) as usize / 2).max(1);
+
+            if selected_index < scroll {
+                scroll = selected_index;
+            } else if selected_index >= scroll + visible {
+                scroll = selected_index + 1 - visible;
+            }
+
+            for (i, issue) in issues.iter().enumerate().skip(scroll).take(visible) {
+                let description = match &issue.kind {
+                    IssueKind::RequiresAddon {
+                        addon, required, ..
+                    } => format!("Addon '{}' requires '{}'", addon, required),
+                    IssueKind::NoPatch(patch) => format!("Missing addon '{}'", patch),
+                    IssueKind::NoFile(path) => format!("Missing file '\\{}'", path),
+                };
+
+                let provider = match issue.provider.and_then(|id| self.mod_manager.find_mod(id)) {
+                    Some(m) if m.enabled => format!("Provided by {} (enabled)", m.name),
+                    Some(m) => format!("Provided by {} (disabled)", m.name),
+                    None => "Not provided by any installed mod".to_string(),
+                };
+
+                // The other errors don't name what asked for the missing addon or file
+                let cause = match &issue.kind {
+                    IssueKind::RequiresAddon { needed_by, .. } => needed_by
+                        .and_then(|id| self.mod_manager.find_mod(id))
+                        .map(|m| format!(", needed by {}", m.name))
+                        .unwrap_or_default(),
+                    IssueKind::NoPatch(_) | IssueKind::NoFile(_) => {
+                        ", the log line doesn't say which mod needs it".to_string()
+                    }
+                };
+
+                let cursor = if i == selected_index { " > " } else { "   " };
+
+                execute!(
+                    stdout,
+                    cursor::MoveTo(0, top_offset),
+                    PrintStyledContent(self.theme.paint(Role::Cursor, cursor)),
+                    cursor::MoveTo(3, top_offset),
+                    Print(description),
+                    cursor::MoveTo(5, top_offset + 1),
+                    PrintStyledContent(self.theme.paint(Role::Muted, provider + &cause)),
+                )?;
+
+                top_offset += 2;
+            }
+
+            stdout.flush()?;
+
+            if let Event::Key(KeyEvent { code, .. }) = event::read()? {
+                match code {
+                    KeyCode::Char('k') | KeyCode::Char('w') | KeyCode::Up => {
+                        selected_index = selected_index.saturating_sub(1);
+                    }
+                    KeyCode::Char('j') | KeyCode::Char('s') | KeyCode::Down
+                        if selected_index + 1 < issues.len() =>
+                    {
+                        selected_index += 1;
+                    }
+                    KeyCode::Enter | KeyCode::Char('e') => {
+                        let provider = issues.get(selected_index).and_then(|i| i.provider);
+
+                        if let Some(m) = provider.and_then(|id| self.mod_manager.find_mod_mut(id)) {
+                            m.enabled = true;
+                        }
+                    }
+                    KeyCode::Esc | KeyCode::Char('q') => break,
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,1366 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::{self, Stdout, Write},
+    process::Command,
+    time::{Duration, SystemTime},
+};
+
+use crossterm::{
+    cursor::{self, SetCursorStyle},
+    event::{self, poll, Event, KeyCode, KeyEvent},
+    execute,
+    style::{Print, PrintStyledContent},
+    terminal,
+};
+
+use crate::errors::{AppError, AppResult};
+
+use self::{
+    guard::TerminalGuard,
+    layout::{HelpPlacement, Layout},
+    mouse::{HitMap, Target},
+    selection::BulkAction,
+};
+
+use super::keymap::{Action, HelpEntry, KeyPress, Keymap, Lookup};
+
+use super::{
+    history::History,
+    integrity::IntegrityReport,
+    process::{self, TrackedProcess},
+    server::{ModSide, ServerProfile},
+    signatures::{SignatureStatus, Verification},
+    theme::{Role, Theme},
+    utils,
+    watcher::WorkshopWatcher,
+    ModChange, ModManager,
+};
+
+mod connections_screen;
+mod details_screen;
+mod guard;
+mod layout;
+mod log_screen;
+mod mouse;
+mod query_screen;
+mod selection;
+mod server_cfg_screen;
+mod servers_screen;
+mod text;
+mod undo;
+
+// What the main loop does after a key press
+enum KeyAction {
+    Render,
+    Ignore,
+    Quit,
+}
+
+// What a key press amounts to with the keys typed before it
+enum KeyInput {
+    // Part of a count or chord, or a cancelled one
+    Waiting,
+    Unbound,
+    Action(Action, Option<usize>),
+}
+
+pub struct Terminal<'a> {
+    mod_manager: &'a mut ModManager,
+    game: Option<TrackedProcess>,
+    launched_at: Option<SystemTime>,
+    // Running dedicated servers by profile name
+    servers: HashMap<String, TrackedProcess>,
+    // Running headless clients by the name of the server profile they connect to
+    headless_clients: HashMap<String, Vec<TrackedProcess>>,
+    // Index of the server profile whose mods are being picked in the mod list
+    editing_profile: Option<usize>,
+    // Results of the integrity checks run from the details view by mod ID
+    integrity: HashMap<u64, IntegrityReport>,
+    // Mods the last diagnostics found needing an addon, with the mod that provides it
+    conflicts: Vec<(u64, Option<u64>)>,
+    // Enabled mods Steam updated while the game was running
+    outdated: HashSet<u64>,
+    watcher: Option<WorkshopWatcher>,
+    // The signature verification running in the background
+    verification: Option<Verification>,
+    // Shown below the title, errors in red and notices in yellow or green
+    status: Option<(Role, String)>,
+    theme: Theme,
+    layout: Layout,
+    // Ticks the selected mod's name has been scrolling for, when it is too long to fit
+    name_scroll: usize,
+    // Count typed before a movement key, like the 5 in 5j
+    count: Option<usize>,
+    // Keys typed so far of a chord, like the first g of gg
+    pending: Vec<KeyPress>,
+    // Whether the next key is the letter to jump to
+    jumping: bool,
+    keymap: Keymap,
+    // Lines of the help panel, generated from the keymap
+    help: Vec<HelpEntry>,
+    // Whether clicks and the scroll wheel are captured
+    mouse: bool,
+    // What was drawn where on the mod list, for clicks
+    hits: HitMap,
+    // Mods marked for a bulk action by ID
+    marked: HashSet<u64>,
+    // Where the visual range started, it runs to the cursor
+    visual_anchor: Option<usize>,
+    // Undo and redo of edits to the enabled mods and parameters
+    history: History,
+}
+
+impl<'a> Terminal<'a> {
+    pub fn new(mod_manager: &'a mut ModManager) -> AppResult<Self> {
+        let keymap = Keymap::new(mod_manager.config.get_keymap())?;
+        let theme = Theme::new(
+            mod_manager.config.get_theme(),
+            mod_manager.config.get_colors(),
+        )?;
+        let help = keymap.help();
+
+        // The list still works without live updates, <R> refreshes it by hand
+        let (watcher, status) = match WorkshopWatcher::new(mod_manager.config.get_workshop_path()) {
+            Ok(watcher) => (Some(watcher), None),
+            Err(e) => (None, Some((Role::Error, e.to_string()))),
+        };
+
+        let (width, height) = terminal::size().unwrap_or((80, 24));
+        let mouse = mod_manager.config.get_mouse();
+
+        mod_manager.loaded_mods.paged = mod_manager.config.get_paged_list();
+
+        let history = if mod_manager.config.get_keep_history() {
+            History::read()
+        } else {
+            History::default()
+        };
+
+        Ok(Terminal {
+            mod_manager,
+            game: None,
+            launched_at: None,
+            servers: HashMap::new(),
+            headless_clients: HashMap::new(),
+            editing_profile: None,
+            integrity: HashMap::new(),
+            conflicts: Vec::new(),
+            outdated: HashSet::new(),
+            watcher,
+            verification: None,
+            status,
+            theme,
+            layout: Layout::new(width, height, &help),
+            name_scroll: 0,
+            count: None,
+            pending: Vec::new(),
+            jumping: false,
+            keymap,
+            help,
+            mouse,
+            hits: HitMap::default(),
+            marked: HashSet::new(),
+            visual_anchor: None,
+            history,
+        })
+    }
+
+    pub fn run(&mut self) -> AppResult<()> {
+        let mut stdout = io::stdout();
+
+        // Restores the terminal however main_loop ends
+        let _guard = TerminalGuard::enter(self.mouse)?;
+
+        self.main_loop(&mut stdout)
+    }
+
+    fn render(&mut self, stdout: &mut Stdout) -> AppResult<()> {
+        self.clear_screen(stdout)?;
+
+        let mut hits = HitMap::default();
+
+        let mut top_offset = 0;
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, top_offset),
+            PrintStyledContent(self.theme.paint(Role::Title, "Arma 3 Mod Manager CLI")),
+        )?;
+
+        if self.mod_manager.has_unsaved_changes() {
+            execute!(
+                stdout,
+                cursor::MoveTo(23, top_offset),
+                PrintStyledContent(self.theme.paint(Role::Warning, "[unsaved]")),
+            )?;
+        }
+
+        let mut pending = self
+            .count
+            .map(|count| count.to_string())
+            .unwrap_or_default();
+
+        pending.extend(self.pending.iter().filter_map(|key| key.char()));
+
+        if self.jumping {
+            pending += " jump to letter";
+        }
+
+        if !pending.is_empty() {
+            execute!(
+                stdout,
+                cursor::MoveTo(33, top_offset),
+                PrintStyledContent(self.theme.paint(Role::Muted, pending)),
+            )?;
+        }
+
+        let selection = match self.visual_anchor {
+            Some(_) => Some("-- VISUAL --".to_string()),
+            None if !self.marked.is_empty() => Some(format!("{} marked", self.marked.len())),
+            None => None,
+        };
+
+        if let Some(selection) = selection {
+            execute!(
+                stdout,
+                cursor::MoveTo(38, top_offset),
+                PrintStyledContent(self.theme.paint(Role::Selection, selection)),
+            )?;
+        }
+
+        top_offset += 1;
+
+        let profile = self.editing_profile();
+
+        if let Some(profile) = profile {
+            execute!(
+                stdout,
+                cursor::MoveTo(0, top_offset),
+                PrintStyledContent(self.theme.paint(
+                    Role::Title,
+                    &format!(
+                        "Server profile '{}': <SPACE> cycles Both/Client/Server, <ESC> to finish",
+                        profile.name
+                    )
+                )),
+            )?;
+        } else if let Some(game) = &self.game {
+            execute!(
+                stdout,
+                cursor::MoveTo(0, top_offset),
+                PrintStyledContent(self.theme.paint(
+                    Role::Success,
+                    &format!(
+                        "Running: PID {} ({})",
+                        game.pid(),
+                        process::format_elapsed(game.elapsed())
+                    )
+                )),
+            )?;
+        } else if let Some((role, status)) = &self.status {
+            execute!(
+                stdout,
+                cursor::MoveTo(0, top_offset),
+                PrintStyledContent(self.theme.paint(*role, status)),
+            )?;
+        }
+
+        top_offset += 1;
+
+        let enabled_mods = match profile {
+            Some(profile) => self
+                .mod_manager
+                .loaded_mods
+                .filter(|m| profile.mods.contains_key(&m.id))
+                .len(),
+            None => self.mod_manager.loaded_mods.filter(|m| m.enabled).len(),
+        };
+        let total_mods = self.mod_manager.loaded_mods.all_items().len();
+
+        let loaded_mods = &self.mod_manager.loaded_mods;
+        let page_number = loaded_mods.current_page() + 1;
+        let total_pages = loaded_mods.total_pages();
+
+        let position = if loaded_mods.paged {
+            format!("Page: {:<2}/{}", page_number, total_pages)
+        } else {
+            format!("Line: {:<2}/{}", loaded_mods.cursor() + 1, total_mods)
+        };
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, top_offset),
+            Print(&format!(
+                "Mods: {:<2}/{}{:^25}{}",
+                enabled_mods, total_mods, " ", position
+            )),
+        )?;
+
+        top_offset += 2;
+
+        self.render_scrollbar(stdout, top_offset, &mut hits)?;
+
+        let first_index = self.mod_manager.loaded_mods.offset();
+        let cursor_index = self.mod_manager.loaded_mods.cursor();
+
+        for (i, m) in self
+            .mod_manager
+            .loaded_mods
+            .current_page_items()
+            .iter()
+            .enumerate()
+        {
+            let mut str: String = String::new();
+            let selected = first_index + i == cursor_index;
+
+            let cursor = if selected { " > " } else { "   " };
+
+            // The whole row up to the scrollbar toggles the mod
+            hits.add(
+                0,
+                top_offset,
+                self.layout.scrollbar_column() as usize,
+                Target::Mod(first_index + i),
+            );
+
+            execute!(
+                stdout,
+                cursor::MoveTo(0, top_offset),
+                PrintStyledContent(self.theme.paint(Role::Cursor, cursor)),
+            )?;
+
+            if self.is_selected(first_index + i, m.id) {
+                execute!(
+                    stdout,
+                    cursor::MoveTo(0, top_offset),
+                    PrintStyledContent(self.theme.paint(Role::Selection, "*")),
+                )?;
+            }
+
+            let mut role = Role::Disabled;
+
+            if let Some(profile) = profile {
+                match profile.mods.get(&m.id) {
+                    Some(side) => {
+                        role = Role::Enabled;
+                        str += &format!("[{}]", side.label());
+                    }
+                    None => str += "[ ]",
+                }
+            } else if m.enabled {
+                role = match m.signature {
+                    Some(SignatureStatus::Invalid(_)) => Role::BadSignature,
+                    _ if self.is_conflicting(m.id) => Role::Conflict,
+                    _ if self.outdated.contains(&m.id) => Role::Outdated,
+                    Some(SignatureStatus::Signed) | None => Role::Enabled,
+                    Some(_) => Role::Unsigned,
+                };
+
+                // Without colour the box tells the problems apart
+                str += match role {
+                    Role::BadSignature if self.theme.is_monochrome() => "[!]",
+                    Role::Conflict if self.theme.is_monochrome() => "[~]",
+                    Role::Outdated if self.theme.is_monochrome() => "[^]",
+                    Role::Unsigned if self.theme.is_monochrome() => "[?]",
+                    _ => "[X]",
+                };
+            } else {
+                str += "[ ]";
+            }
+
+            // The selected name scrolls through, the others end in an ellipsis
+            let label = self.mod_label(m);
+            let name = if selected {
+                text::scroll(&label, self.layout.name_width, self.name_scroll)
+            } else {
+                text::truncate(&label, self.layout.name_width)
+            };
+            str += &format!(" {}", name);
+
+            execute!(
+                stdout,
+                cursor::MoveTo(3, top_offset),
+                PrintStyledContent(self.theme.paint(role, str)),
+            )?;
+
+            let change = match m.change {
+                Some(ModChange::New) => Some((Role::New, "NEW")),
+                Some(ModChange::Updated) => Some((Role::Updated, "UPD")),
+                None => None,
+            };
+
+            if let Some((role, label)) = change {
+                execute!(
+                    stdout,
+                    cursor::MoveTo(self.layout.marker_column(), top_offset),
+                    PrintStyledContent(self.theme.paint(role, label)),
+                )?;
+            }
+
+            top_offset += 1;
+        }
+
+        // Show pagination direction
+
+        let selected_signature = self.selected_mod().and_then(|m| m.signature.as_ref());
+
+        if let (None, Some(signature)) = (profile, selected_signature) {
+            let role = match signature {
+                SignatureStatus::Signed => Role::Success,
+                SignatureStatus::Invalid(_) => Role::BadSignature,
+                _ => Role::Unsigned,
+            };
+
+            execute!(
+                stdout,
+                cursor::MoveTo(3, top_offset + 1),
+                PrintStyledContent(
+                    self.theme
+                        .paint(role, &format!("Signature: {}", signature.label()))
+                ),
+            )?;
+        }
+
+        if self.mod_manager.loaded_mods.paged && page_number > 1 {
+            execute!(stdout, cursor::MoveTo(0, top_offset), Print("<--"))?;
+            hits.add(0, top_offset, 3, Target::Action(Action::PageUp));
+        }
+
+        if self.mod_manager.loaded_mods.paged && page_number < total_pages {
+            let column = self.layout.marker_column() - 4;
+
+            execute!(stdout, cursor::MoveTo(column, top_offset), Print("-->"))?;
+            hits.add(column, top_offset, 3, Target::Action(Action::PageDown));
+        }
+
+        self.render_help(stdout, &mut hits)?;
+        self.hits = hits;
+
+        stdout.flush()?;
+
+        Ok(())
+    }
+
+    // Track and thumb right of the list showing which part of it is visible, clicking the track
+    // above or below the thumb pages up or down
+    fn render_scrollbar(
+        &self,
+        stdout: &mut Stdout,
+        top_offset: u16,
+        hits: &mut HitMap,
+    ) -> AppResult<()> {
+        let loaded_mods = &self.mod_manager.loaded_mods;
+        let total = loaded_mods.all_items().len();
+        let rows = loaded_mods.page_size;
+
+        if total <= rows {
+            return Ok(());
+        }
+
+        let thumb_size = (rows * rows / total).max(1);
+        let max_offset = total - rows;
+        let thumb_start = (loaded_mods.offset().min(max_offset) * (rows - thumb_size)
+            + max_offset / 2)
+            / max_offset;
+
+        for row in 0..rows {
+            let thumb = (thumb_start..thumb_start + thumb_size).contains(&row);
+            let (column, y) = (self.layout.scrollbar_column(), top_offset + row as u16);
+
+            execute!(
+                stdout,
+                cursor::MoveTo(column, y),
+                PrintStyledContent(self.theme.paint(Role::Faint, if thumb { "█" } else { "│" })),
+            )?;
+
+            if row < thumb_start {
+                hits.add(column, y, 1, Target::Action(Action::PageUp));
+            } else if !thumb {
+                hits.add(column, y, 1, Target::Action(Action::PageDown));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn render_help(&self, stdout: &mut Stdout, hits: &mut HitMap) -> AppResult<()> {
+        let info_left_offset = match &self.layout.help {
+            HelpPlacement::Side(column) => *column,
+            HelpPlacement::Footer(lines) => {
+                let top = self.layout.height.saturating_sub(lines.len() as u16);
+
+                for (i, line) in lines.iter().enumerate() {
+                    let row = top + i as u16;
+                    let mut column = 0;
+
+                    for (n, entry) in line.iter().map(|index| &self.help[*index]).enumerate() {
+                        if n > 0 {
+                            column += layout::FOOTER_SEPARATOR.len() as u16;
+                        }
+
+                        let footer_entry = layout::footer_entry(entry);
+                        let text_column = column + text::width(&entry.keybinding()) as u16 + 1;
+
+                        hits.add_help_entry(text_column, column, row, entry);
+
+                        execute!(
+                            stdout,
+                            cursor::MoveTo(column, row),
+                            PrintStyledContent(self.theme.paint(Role::Title, &footer_entry)),
+                        )?;
+
+                        column += text::width(&footer_entry) as u16;
+                    }
+                }
+
+                return Ok(());
+            }
+        };
+
+        let top_offset = 2;
+        let info_text_padding = layout::HELP_PADDING;
+
+        execute!(
+            stdout,
+            cursor::MoveTo(info_left_offset, top_offset),
+            Print(&format!(
+                "{:<padding$}{}",
+                "Action",
+                "Keybindings",
+                padding = info_text_padding
+            )),
+        )?;
+
+        for (i, entry) in self.help.iter().enumerate() {
+            let y_offset = top_offset + 2 + i as u16; // Adjust starting y offset as needed
+
+            if y_offset >= self.layout.height {
+                break;
+            }
+
+            hits.add_help_entry(
+                info_left_offset,
+                info_left_offset + info_text_padding as u16,
+                y_offset,
+                entry,
+            );
+
+            execute!(
+                stdout,
+                cursor::MoveTo(info_left_offset, y_offset),
+                PrintStyledContent(self.theme.paint(
+                    Role::Title,
+                    &format!(
+                        "{:<padding$}{}",
+                        entry.text,
+                        entry.keybinding(),
+                        padding = info_text_padding
+                    )
+                )),
+            )?;
+        }
+
+        //execute!(
+        //    stdout,
+        //    cursor::MoveTo(info_left_offset, top_offset),
+        //    Print("For more information visit: github.com/viktorholk/arma3-mod-manager-cli"),
+        //)?;
+
+        Ok(())
+    }
+
+    fn main_loop(&mut self, stdout: &mut Stdout) -> AppResult<()> {
+        self.fit_to_terminal()?;
+        self.render(stdout)?;
+        stdout.flush()?;
+
+        loop {
+            // Tick faster while the selected name scrolls or signatures are verified
+            let timeout = if self.selected_name_overflows() || self.verification.is_some() {
+                Duration::from_millis(300)
+            } else {
+                Duration::from_millis(1000)
+            };
+
+            if poll(timeout)? {
+                // A click moves the cursor, so this is taken before reading it
+                let selected_id = self.selected_mod().map(|m| m.id);
+
+                let input = match event::read()? {
+                    Event::Key(event) => self.read_key(event),
+                    Event::Mouse(event) => self.read_mouse(event),
+                    Event::Resize(..) => {
+                        self.fit_to_terminal()?;
+                        self.render(stdout)?;
+                        continue;
+                    }
+                    _ => continue,
+                };
+
+                let result = match input {
+                    KeyInput::Waiting => Ok(KeyAction::Render),
+                    KeyInput::Unbound => Ok(KeyAction::Ignore),
+                    // Undo and redo move through the history instead of adding to it
+                    KeyInput::Action(action @ (Action::Undo | Action::Redo), count) => {
+                        self.handle_action(stdout, action, count)
+                    }
+                    KeyInput::Action(action, count) => {
+                        let before = self.mod_manager.snapshot();
+                        let result = self.run_action(stdout, action, count);
+
+                        if let Err(e) = self.record_edit(before) {
+                            self.status = Some((Role::Error, e.to_string()));
+                        }
+
+                        result
+                    }
+                };
+
+                // A failed action is shown in the status line, only a broken terminal ends the app
+                match result {
+                    Ok(KeyAction::Render) => {}
+                    Ok(KeyAction::Ignore) => continue,
+                    Ok(KeyAction::Quit) => break,
+                    Err(e) => self.status = Some((Role::Error, e.to_string())),
+                }
+
+                if self.mod_manager.config.get_autosave() && self.mod_manager.has_unsaved_changes()
+                {
+                    if let Err(e) = self.mod_manager.save_enabled_mods() {
+                        self.status = Some((Role::Error, e.to_string()));
+                    }
+                }
+
+                if self.selected_mod().map(|m| m.id) != selected_id {
+                    self.name_scroll = 0;
+                }
+
+                // The terminal may have been resized while another screen was open
+                self.fit_to_terminal()?;
+                self.render(stdout)?;
+                stdout.flush()?;
+            } else {
+                let mut changed = match self.check_workshop() {
+                    Ok(changed) => changed,
+                    Err(e) => {
+                        self.status = Some((Role::Error, e.to_string()));
+                        true
+                    }
+                };
+
+                if self.game.is_some()
+                    || !self.servers.is_empty()
+                    || !self.headless_clients.is_empty()
+                {
+                    // Keep the elapsed time ticking and pick up when the game exits
+                    if let Err(e) = self.check_processes() {
+                        self.status = Some((Role::Error, e.to_string()));
+                    }
+                    changed = true;
+                }
+
+                if self.selected_name_overflows() {
+                    self.name_scroll += 1;
+                    changed = true;
+                }
+
+                if self.verification.is_some() {
+                    self.check_verification();
+                    changed = true;
+                }
+
+                if changed {
+                    self.render(stdout)?;
+                    stdout.flush()?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Turns the key into an action once the keys typed so far make up a chord
+    fn read_key(&mut self, event: KeyEvent) -> KeyInput {
+        let key = KeyPress::from_event(&event);
+
+        if self.jumping {
+            self.jumping = false;
+            self.count = None;
+
+            if let Some(letter) = key.char() {
+                self.jump_to_letter(letter);
+            }
+
+            return KeyInput::Waiting;
+        }
+
+        // Digits are counts, a 0 only after another digit
+        if let Some(digit) = key.char().and_then(|c| c.to_digit(10)) {
+            if self.pending.is_empty() && (digit != 0 || self.count.is_some()) {
+                let count = self.count.unwrap_or(0).saturating_mul(10);
+                self.count = Some(count.saturating_add(digit as usize));
+
+                return KeyInput::Waiting;
+            }
+        }
+
+        self.pending.push(key);
+
+        match self.keymap.lookup(&self.pending) {
+            Lookup::Prefix => KeyInput::Waiting,
+            Lookup::Action(action) => {
+                self.pending.clear();
+                KeyInput::Action(action, self.count.take())
+            }
+            Lookup::Unbound => {
+                // Anything else cancels a count or an unfinished chord
+                let cancelled = self.pending.len() > 1 || self.count.is_some();
+
+                self.pending.clear();
+                self.count = None;
+
+                if cancelled {
+                    KeyInput::Waiting
+                } else {
+                    KeyInput::Unbound
+                }
+            }
+        }
+    }
+
+    fn handle_action(
+        &mut self,
+        stdout: &mut Stdout,
+        action: Action,
+        count: Option<usize>,
+    ) -> AppResult<KeyAction> {
+        let repeat = count.unwrap_or(1);
+
+        match action {
+            Action::Save => {
+                self.mod_manager.save_enabled_mods()?;
+                self.status = Some((Role::Success, "Saved".to_string()));
+            }
+
+            Action::Up => {
+                self.mod_manager.loaded_mods.move_cursor(-(repeat as isize));
+            }
+            Action::Down => {
+                self.mod_manager.loaded_mods.move_cursor(repeat as isize);
+            }
+
+            Action::PageUp => {
+                (0..repeat).for_each(|_| self.mod_manager.loaded_mods.prev_page());
+            }
+
+            Action::PageDown => {
+                (0..repeat).for_each(|_| self.mod_manager.loaded_mods.next_page());
+            }
+
+            // Like in vim, a count goes to that line
+            Action::Top => {
+                let line = count.unwrap_or(1);
+                self.mod_manager
+                    .loaded_mods
+                    .set_cursor(line.saturating_sub(1));
+            }
+            Action::Bottom => {
+                let line = count.unwrap_or(usize::MAX);
+                self.mod_manager
+                    .loaded_mods
+                    .set_cursor(line.saturating_sub(1));
+            }
+
+            Action::JumpToLetter => self.jumping = true,
+
+            Action::ToggleAll if self.editing_profile.is_some() => {
+                let ids = self
+                    .mod_manager
+                    .loaded_mods
+                    .all_items()
+                    .iter()
+                    .map(|m| m.id)
+                    .collect::<Vec<_>>();
+
+                if let Some(profile) = self.editing_profile_mut() {
+                    let side = if ids.iter().all(|id| profile.mods.contains_key(id)) {
+                        None
+                    } else {
+                        Some(ModSide::Both)
+                    };
+
+                    ids.iter().for_each(|id| profile.set_side(*id, side));
+                }
+            }
+
+            Action::ToggleAll => {
+                let value = !self
+                    .mod_manager
+                    .loaded_mods
+                    .all_items()
+                    .iter()
+                    .all(|m| m.enabled);
+
+                self.mod_manager
+                    .loaded_mods
+                    .all_items_mut()
+                    .iter_mut()
+                    .for_each(|m| m.enabled = value);
+            }
+
+            Action::Toggle => {
+                let Some(selected_mod) = self.mod_manager.loaded_mods.selected_mut() else {
+                    return Ok(KeyAction::Ignore);
+                };
+
+                if self.editing_profile.is_none() {
+                    selected_mod.enabled = !selected_mod.enabled;
+                } else {
+                    let id = selected_mod.id;
+
+                    if let Some(profile) = self.editing_profile_mut() {
+                        profile.set_side(id, ModSide::next(profile.mods.get(&id).copied()));
+                    }
+                }
+            }
+
+            Action::Details if self.editing_profile.is_none() => {
+                let selected = self.selected_mod().map(|m| m.id);
+
+                if let Some(id) = selected {
+                    self.details_screen(stdout, id)?;
+                }
+            }
+
+            Action::Details => return Ok(KeyAction::Ignore),
+
+            Action::Undo => self.step_history(true, repeat)?,
+            Action::Redo => self.step_history(false, repeat)?,
+
+            Action::Refresh => {
+                self.mod_manager.refresh_mods()?;
+            }
+            Action::Parameters => {
+                self.set_custom_parameters_screen(stdout)?;
+            }
+            Action::Launch => {
+                self.start_game()?;
+            }
+            Action::CheckSignatures => {
+                self.check_signatures();
+            }
+            Action::VerifySignatures => {
+                self.start_verification();
+            }
+            Action::SavedServers => {
+                self.connections_screen(stdout)?;
+            }
+            Action::ServerProfiles => {
+                self.servers_screen(stdout)?;
+            }
+            Action::GameLog => {
+                self.log_screen(stdout)?;
+            }
+            Action::StopGame => {
+                if let Some(mut game) = self.game.take() {
+                    game.kill()?;
+                }
+            }
+
+            Action::Mark => self.toggle_mark(),
+            Action::Visual => self.toggle_visual(),
+            Action::Enable => self.apply_to_selection(BulkAction::Enable),
+            Action::Disable => self.apply_to_selection(BulkAction::Disable),
+            Action::Invert => self.apply_to_selection(BulkAction::Invert),
+            Action::Tag => self.tag_selection(stdout)?,
+            Action::EnableMatching => self.apply_to_matching(stdout, BulkAction::Enable)?,
+            Action::DisableMatching => self.apply_to_matching(stdout, BulkAction::Disable)?,
+
+            Action::Back if self.has_selection() => self.clear_selection(),
+            Action::Back if self.editing_profile.is_some() => {
+                self.editing_profile = None;
+                self.mod_manager.config.save()?;
+                self.servers_screen(stdout)?;
+            }
+            Action::Back if self.mod_manager.has_unsaved_changes() => {
+                if self.confirm_quit(stdout)? {
+                    return Ok(KeyAction::Quit);
+                }
+            }
+            Action::Back => return Ok(KeyAction::Quit),
+        }
+
+        Ok(KeyAction::Render)
+    }
+
+    // Asks whether to save before quitting, returns false when quitting was cancelled
+    fn confirm_quit(&mut self, stdout: &mut Stdout) -> AppResult<bool> {
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 1),
+            terminal::Clear(terminal::ClearType::CurrentLine),
+            PrintStyledContent(self.theme.paint(
+                Role::Warning,
+                "Unsaved changes: <Y> save and quit, <N> quit without saving, <ESC> cancel"
+            )),
+        )?;
+        stdout.flush()?;
+
+        loop {
+            if let Event::Key(KeyEvent { code, .. }) = event::read()? {
+                match code {
+                    KeyCode::Char('y') | KeyCode::Enter => {
+                        self.mod_manager.save_enabled_mods()?;
+                        return Ok(true);
+                    }
+                    KeyCode::Char('n') => return Ok(true),
+                    KeyCode::Esc => return Ok(false),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // Reads a line of text on the status line, None when cancelled with <ESC>
+    fn prompt(&mut self, stdout: &mut Stdout, label: &str) -> AppResult<Option<String>> {
+        let mut input = String::new();
+
+        execute!(stdout, cursor::Show)?;
+
+        let result = loop {
+            execute!(
+                stdout,
+                cursor::MoveTo(0, 1),
+                terminal::Clear(terminal::ClearType::CurrentLine),
+                PrintStyledContent(self.theme.paint(Role::Warning, label)),
+                Print(&input)
+            )?;
+            stdout.flush()?;
+
+            if let Event::Key(KeyEvent { code, .. }) = event::read()? {
+                match code {
+                    KeyCode::Enter => break Some(input),
+                    KeyCode::Esc => break None,
+                    KeyCode::Backspace => {
+                        input.pop();
+                    }
+                    KeyCode::Char(c) => input.push(c),
+                    _ => {}
+                }
+            }
+        };
+
+        execute!(stdout, cursor::Hide)?;
+
+        Ok(result)
+    }
+
+    // Applies the mods Steam added, updated or removed, keeping the cursor on the same mod
+    fn check_workshop(&mut self) -> AppResult<bool> {
+        let Some(changed) = self.watcher.as_mut().and_then(|w| w.poll()) else {
+            return Ok(false);
+        };
+
+        let selected_id = self.selected_mod().map(|m| m.id);
+        let removed = self.mod_manager.apply_changes(&changed)?;
+
+        // The running game keeps the version it loaded until it is launched again
+        if self.game.is_some() {
+            let updated = changed
+                .iter()
+                .filter(|id| self.mod_manager.find_mod(**id).is_some_and(|m| m.enabled))
+                .collect::<Vec<_>>();
+
+            self.outdated.extend(updated);
+        }
+
+        if !removed.is_empty() {
+            self.status = Some((Role::Warning, format!("Removed: {}", removed.join(", "))));
+        }
+
+        let items = self.mod_manager.loaded_mods.all_items();
+
+        if let Some(index) = selected_id.and_then(|id| items.iter().position(|m| m.id == id)) {
+            self.mod_manager.loaded_mods.set_cursor(index);
+        }
+
+        if let Some(watcher) = &mut self.watcher {
+            watcher.watch_mods(&changed)?;
+        }
+
+        Ok(true)
+    }
+
+    // Moves to the next mod starting with the letter, going round to the top after the last
+    fn jump_to_letter(&mut self, letter: char) {
+        let letter = letter.to_lowercase().collect::<String>();
+        let loaded_mods = &mut self.mod_manager.loaded_mods;
+        let items = loaded_mods.all_items();
+        let cursor = loaded_mods.cursor();
+
+        let found = (1..=items.len())
+            .map(|step| (cursor + step) % items.len())
+            .find(|&index| items[index].name.to_lowercase().starts_with(&letter));
+
+        if let Some(index) = found {
+            loaded_mods.set_cursor(index);
+        }
+    }
+
+    // Lays the screen out for the current terminal size, keeping the cursor on the same mod
+    fn fit_to_terminal(&mut self) -> AppResult<()> {
+        let (width, height) = terminal::size()?;
+        self.layout = Layout::new(width, height, &self.help);
+
+        self.mod_manager
+            .loaded_mods
+            .set_page_size(self.layout.page_size);
+
+        Ok(())
+    }
+
+    fn selected_name_overflows(&self) -> bool {
+        self.selected_mod()
+            .is_some_and(|m| text::width(&self.mod_label(m)) > self.layout.name_width)
+    }
+
+    // Whether the mod still misses an addon, which enabling its provider resolves
+    fn is_conflicting(&self, id: u64) -> bool {
+        self.conflicts.iter().any(|(needed_by, provider)| {
+            *needed_by == id
+                && !provider
+                    .and_then(|p| self.mod_manager.find_mod(p))
+                    .is_some_and(|p| p.enabled)
+        })
+    }
+
+    fn selected_mod(&self) -> Option<&super::Mod> {
+        self.mod_manager.loaded_mods.selected()
+    }
+
+    fn check_processes(&mut self) -> AppResult<()> {
+        if let Some(game) = &mut self.game {
+            if let Some(exit_status) = game.try_wait()? {
+                if !exit_status.success() {
+                    self.status = Some((
+                        Role::Error,
+                        AppError::ProcessExited(
+                            exit_status.to_string(),
+                            game.log_path().to_string_lossy().to_string(),
+                        )
+                        .to_string(),
+                    ));
+                }
+
+                self.game = None;
+                self.outdated.clear();
+            }
+        }
+
+        let mut exited = Vec::new();
+
+        for (name, server) in &mut self.servers {
+            if let Some(exit_status) = server.try_wait()? {
+                if !exit_status.success() {
+                    self.status = Some((
+                        Role::Error,
+                        AppError::ProcessExited(
+                            exit_status.to_string(),
+                            server.log_path().to_string_lossy().to_string(),
+                        )
+                        .to_string(),
+                    ));
+                }
+
+                exited.push(name.clone());
+            }
+        }
+
+        for name in exited {
+            self.servers.remove(&name);
+        }
+
+        for clients in self.headless_clients.values_mut() {
+            let mut running = Vec::new();
+
+            for mut client in clients.drain(..) {
+                match client.try_wait()? {
+                    Some(exit_status) if !exit_status.success() => {
+                        self.status = Some((
+                            Role::Error,
+                            AppError::ProcessExited(
+                                exit_status.to_string(),
+                                client.log_path().to_string_lossy().to_string(),
+                            )
+                            .to_string(),
+                        ));
+                    }
+                    Some(_) => {}
+                    None => running.push(client),
+                }
+            }
+
+            *clients = running;
+        }
+
+        self.headless_clients
+            .retain(|_, clients| !clients.is_empty());
+
+        Ok(())
+    }
+
+    fn check_signatures(&mut self) {
+        self.mod_manager.check_signatures(false);
+        self.report_signatures();
+    }
+
+    // Verifies the enabled mods in the background, the list stays usable meanwhile
+    fn start_verification(&mut self) {
+        if self.verification.is_some() {
+            return;
+        }
+
+        let workshop_path = self.mod_manager.config.get_workshop_path();
+        let mods = self
+            .mod_manager
+            .loaded_mods
+            .filter(|m| m.enabled)
+            .iter()
+            .map(|m| (m.id, m.get_path(workshop_path)))
+            .collect::<Vec<_>>();
+
+        self.verification = Some(Verification::start(mods));
+        self.check_verification();
+    }
+
+    fn check_verification(&mut self) {
+        let Some(verification) = &mut self.verification else {
+            return;
+        };
+
+        let results = verification.poll();
+        let (done, total) = (verification.done, verification.total);
+        let finished = verification.is_finished();
+
+        for (id, signature) in results {
+            if let Some(m) = self.mod_manager.find_mod_mut(id) {
+                m.signature = Some(signature);
+            }
+        }
+
+        if finished {
+            self.verification = None;
+            self.report_signatures();
+        } else {
+            self.status = Some((
+                Role::Muted,
+                format!("Verifying signatures {}/{}", done, total),
+            ));
+        }
+    }
+
+    fn report_signatures(&mut self) {
+        let problems = self
+            .mod_manager
+            .loaded_mods
+            .filter(|m| m.enabled && !matches!(m.signature, Some(SignatureStatus::Signed)))
+            .len();
+
+        self.status = match problems {
+            0 => None,
+            n => Some((
+                Role::Warning,
+                format!("{} enabled mods are not properly signed", n),
+            )),
+        };
+    }
+
+    fn editing_profile(&self) -> Option<&ServerProfile> {
+        self.editing_profile
+            .and_then(|i| self.mod_manager.config.get_server_profiles().get(i))
+    }
+
+    fn editing_profile_mut(&mut self) -> Option<&mut ServerProfile> {
+        self.editing_profile
+            .and_then(|i| self.mod_manager.config.get_server_profiles_mut().get_mut(i))
+    }
+
+    fn start_game(&mut self) -> AppResult<()> {
+        self.launch_game(Vec::new())
+    }
+
+    // Deploys the enabled mods and starts the game with the extra args after the defaults
+    fn launch_game(&mut self, extra_args: Vec<String>) -> AppResult<()> {
+        if self.game.is_some() {
+            return Err(AppError::AlreadyRunning);
+        }
+
+        let enabled_mods = self.mod_manager.enabled_mod_ids();
+        let game_path = self.mod_manager.config.get_game_path();
+        let workshop_path = self.mod_manager.config.get_workshop_path();
+
+        let game_app_path = game_path.join("arma3.app");
+        let game_app_path_str = game_app_path.to_string_lossy().to_string();
+
+        if !game_app_path.exists() {
+            return Err(AppError::InvalidPath(game_app_path_str.to_owned()));
+        }
+
+        let mut command = Command::new(utils::get_bundle_executable(&game_app_path)?);
+
+        let mod_paths = enabled_mods
+            .iter()
+            .map(|id| workshop_path.join(id.to_string()))
+            .collect::<Vec<_>>();
+
+        // Deploy the enabled mods into the game directory, removing the ones deployed earlier
+        super::deploy::deploy(
+            self.mod_manager.config.get_deploy_strategy(),
+            self.mod_manager.config.get_lowercase_mods(),
+            game_path,
+            mod_paths,
+        )?;
+
+        // Build args
+        let default_args = self.mod_manager.config.get_default_args();
+        command.args(default_args.split_whitespace());
+        command.args(extra_args);
+
+        if !enabled_mods.is_empty() {
+            command.arg(format!(
+                "-mod={}",
+                enabled_mods
+                    .iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<String>>()
+                    .join(";")
+            ));
+        }
+
+        // Save the enabled mods so it loads next time
+        self.mod_manager.save_enabled_mods()?;
+
+        self.status = None;
+        self.game = Some(TrackedProcess::spawn(command, "game")?);
+        self.launched_at = Some(SystemTime::now());
+
+        Ok(())
+    }
+
+    fn clear_screen(&self, stdout: &mut Stdout) -> AppResult<()> {
+        execute!(stdout, cursor::MoveTo(0, 0))?;
+        execute!(stdout, terminal::Clear(terminal::ClearType::All))?;
+
+        Ok(())
+    }
+
+    fn set_custom_parameters_screen(&mut self, stdout: &mut Stdout) -> AppResult<()> {
+        let mut args_string = self.mod_manager.config.get_default_args().to_string();
+        let mut current_pos = args_string.len() as u16;
+
+        // Set up the terminal
+
+        execute!(stdout, cursor::Show)?;
+        execute!(stdout, SetCursorStyle::BlinkingUnderScore)?;
+        stdout.flush()?;
+
+        self.clear_screen(stdout)?;
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 0),
+            PrintStyledContent(self.theme.paint(Role::Title, "Arma 3 Mod Manager CLI")),
+        )?;
+
+        execute!(stdout, cursor::MoveTo(0, 2), Print("Press <ENTER> to save"),)?;
+
+        let arg_string_left = 4;
+        let arg_string_top = 4;
+        let arg_string_left_padding = arg_string_left - 3;
+
+        execute!(
+            stdout,
+            cursor::MoveTo(arg_string_left_padding, arg_string_top),
+            PrintStyledContent(self.theme.paint(Role::Cursor, ">")),
+        )?;
+
+        execute!(
+            stdout,
+            cursor::MoveTo(arg_string_left, arg_string_top),
+            Print(&args_string)
+        )?;
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, arg_string_top + 2),
+            Print("For more information visit: https://community.bistudio.com/wiki/Arma_3:_Startup_Parameters")
+        )?;
+
+        execute!(
+            stdout,
+            cursor::MoveTo(current_pos + arg_string_left, arg_string_top)
+        )?;
+
+        loop {
+            if event::poll(Duration::from_millis(500))? {
+                if let Event::Key(KeyEvent { code, .. }) = event::read()? {
+                    match code {
+                        KeyCode::Esc => {
+                            break;
+                        }
+                        KeyCode::Enter => {
+                            self.mod_manager.config.set_default_args(args_string);
+                            self.mod_manager.config.save()?;
+
+                            break;
+                        }
+                        KeyCode::Backspace if !args_string.is_empty() && current_pos > 0 => {
+                            args_string.pop();
+                            current_pos -= 1;
+                        }
+                        KeyCode::Char(c) => {
+                            args_string.push(c);
+                            current_pos += 1;
+                        }
+                        _ => {}
+                    }
+
+                    execute!(stdout, terminal::Clear(terminal::ClearType::CurrentLine))?;
+
+                    execute!(
+                        stdout,
+                        cursor::MoveTo(arg_string_left_padding, arg_string_top),
+                        PrintStyledContent(self.theme.paint(Role::Cursor, ">")),
+                    )?;
+
+                    // Clear the previous line and update display
+                    execute!(
+                        stdout,
+                        cursor::MoveTo(arg_string_left, arg_string_top),
+                        Print(&args_string)
+                    )?;
+
+                    execute!(
+            stdout,
+            cursor::MoveTo(0, arg_string_top + 2),
+            Print("For more information visit: https://community.bistudio.com/wiki/Arma_3:_Startup_Parameters")
+        )?;
+
+                    // Move cursor to the new position
+                    execute!(
+                        stdout,
+                        cursor::MoveTo(current_pos + arg_string_left, arg_string_top)
+                    )?;
+
+                    stdout.flush()?;
+                }
+            }
+        }
+
+        // Restore terminal state
+        execute!(stdout, cursor::Hide)?;
+        execute!(stdout, SetCursorStyle::DefaultUserShape)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use std::{io::Stdout, ops::Range};
+
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture, MouseButton, MouseEvent, MouseEventKind},
+    execute,
+};
+
+use crate::errors::AppResult;
+
+use super::{
+    super::keymap::{Action, HelpEntry},
+    text, KeyAction, KeyInput, Terminal,
+};
+
+// Mods the cursor moves per step of the scroll wheel
+const WHEEL_STEP: usize = 3;
+
+// What a click on part of the screen does
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Target {
+    // The mod at the index of the whole list
+    Mod(usize),
+    Action(Action),
+}
+
+// Where the last render drew the things that can be clicked
+#[derive(Debug, Default)]
+pub(super) struct HitMap {
+    areas: Vec<(u16, Range<u16>, Target)>,
+}
+
+impl HitMap {
+    pub fn add(&mut self, column: u16, row: u16, width: usize, target: Target) {
+        let end = column.saturating_add(width as u16);
+        self.areas.push((row, column..end, target));
+    }
+
+    // The keys of each action run that action, the text runs the first one
+    pub fn add_help_entry(
+        &mut self,
+        text_column: u16,
+        keys_column: u16,
+        row: u16,
+        entry: &HelpEntry,
+    ) {
+        if let Some((action, _)) = entry.keys.first() {
+            self.add(
+                text_column,
+                row,
+                text::width(entry.text),
+                Target::Action(*action),
+            );
+        }
+
+        let mut column = keys_column;
+
+        for (action, label) in &entry.keys {
+            let width = text::width(label);
+            self.add(column, row, width, Target::Action(*action));
+            column += (width + HelpEntry::KEY_SEPARATOR.len()) as u16;
+        }
+    }
+
+    pub fn find(&self, column: u16, row: u16) -> Option<Target> {
+        self.areas
+            .iter()
+            .find(|(area_row, columns, _)| *area_row == row && columns.contains(&column))
+            .map(|(_, _, target)| *target)
+    }
+}
+
+impl Terminal<'_> {
+    // Turns a click or the scroll wheel into the action it stands for
+    pub(super) fn read_mouse(&mut self, event: MouseEvent) -> KeyInput {
+        let input = match event.kind {
+            MouseEventKind::ScrollUp => KeyInput::Action(Action::Up, Some(WHEEL_STEP)),
+            MouseEventKind::ScrollDown => KeyInput::Action(Action::Down, Some(WHEEL_STEP)),
+            MouseEventKind::Down(MouseButton::Left) => {
+                match self.hits.find(event.column, event.row) {
+                    Some(Target::Mod(index)) => {
+                        self.mod_manager.loaded_mods.set_cursor(index);
+                        KeyInput::Action(Action::Toggle, None)
+                    }
+                    Some(Target::Action(action)) => KeyInput::Action(action, None),
+                    None => KeyInput::Unbound,
+                }
+            }
+            _ => return KeyInput::Unbound,
+        };
+
+        // Like any other key, the mouse cancels an unfinished count or chord
+        self.count = None;
+        self.pending.clear();
+        self.jumping = false;
+
+        input
+    }
+
+    // The other screens only take keys and redraw on every event, so mouse capture is off while
+    // one is open, or moving the mouse would make it flicker
+    pub(super) fn run_action(
+        &mut self,
+        stdout: &mut Stdout,
+        action: Action,
+        count: Option<usize>,
+    ) -> AppResult<KeyAction> {
+        let opens_screen = matches!(
+            action,
+            Action::Details
+                | Action::Parameters
+                | Action::GameLog
+                | Action::ServerProfiles
+                | Action::SavedServers
+                | Action::Back
+        );
+
+        if !self.mouse || !opens_screen {
+            return self.handle_action(stdout, action, count);
+        }
+
+        execute!(stdout, DisableMouseCapture)?;
+        let result = self.handle_action(stdout, action, count);
+        execute!(stdout, EnableMouseCapture)?;
+
+        result
+    }
+}
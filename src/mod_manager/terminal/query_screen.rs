@@ -0,0 +1,269 @@
+use std::{
+    io::{Stdout, Write},
+    time::Duration,
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent},
+    execute,
+    style::{Print, PrintStyledContent},
+    terminal,
+};
+
+use crate::{
+    errors::AppResult,
+    mod_manager::a2s::{self, ArmaRules, ServerInfo},
+};
+
+use super::{super::theme::Role, text, Terminal};
+
+const QUERY_TIMEOUT: Duration = Duration::from_secs(3);
+
+impl Terminal<'_> {
+    // Queries the saved server and shows its state and the mods it requires
+    pub(super) fn query_screen(&mut self, stdout: &mut Stdout, index: usize) -> AppResult<()> {
+        let mut result = None;
+        let mut scroll = 0;
+        let mut message: Option<(Role, String)> = None;
+
+        loop {
+            let Some((info, rules)) = &result else {
+                self.render_querying(stdout, index)?;
+
+                match self.query_server(index) {
+                    Ok((info, Ok(rules))) => result = Some((Some(info), rules)),
+                    // The server is still shown when only its mod list can't be read
+                    Ok((info, Err(e))) => {
+                        message =
+                            Some((Role::Error, format!("Cannot read the required mods: {}", e)));
+                        result = Some((Some(info), ArmaRules::default()));
+                    }
+                    Err(e) => {
+                        message = Some((Role::Error, e.to_string()));
+                        result = Some((None, ArmaRules::default()));
+                    }
+                }
+                continue;
+            };
+
+            self.render_query_screen(stdout, index, info.as_ref(), rules, scroll, &message)?;
+
+            let Event::Key(KeyEvent { code, .. }) = event::read()? else {
+                continue;
+            };
+
+            match code {
+                KeyCode::Char('k') | KeyCode::Char('w') | KeyCode::Up => {
+                    scroll = scroll.saturating_sub(1);
+                }
+                KeyCode::Char('j') | KeyCode::Char('s') | KeyCode::Down
+                    if scroll + 1 < rules.mods.len() =>
+                {
+                    scroll += 1;
+                }
+                KeyCode::Char('a') if !rules.mods.is_empty() => {
+                    let missing = self.apply_server_mods(index, rules)?;
+
+                    message = Some(if missing == 0 {
+                        (Role::Success, "Enabled all required mods".to_string())
+                    } else {
+                        (
+                            Role::Warning,
+                            format!("Enabled the installed mods, {} are missing", missing),
+                        )
+                    });
+                }
+                KeyCode::Char('r') => {
+                    result = None;
+                    message = None;
+                }
+                KeyCode::Esc | KeyCode::Char('q') => break,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    // The rules are queried separately, so their error doesn't hide the server info
+    fn query_server(&self, index: usize) -> AppResult<(ServerInfo, AppResult<ArmaRules>)> {
+        let connection = &self.mod_manager.config.get_connections()[index];
+        let client =
+            a2s::Client::connect(&connection.host, connection.get_query_port(), QUERY_TIMEOUT)?;
+
+        let info = client.info()?;
+
+        Ok((info, client.rules()))
+    }
+
+    // Enables exactly the installed mods the server requires and saves them as its modset
+    //
+    // Returns the number of required mods that are not installed
+    fn apply_server_mods(&mut self, index: usize, rules: &ArmaRules) -> AppResult<usize> {
+        let ids = rules
+            .mods
+            .iter()
+            .filter(|m| !m.dlc)
+            .map(|m| m.workshop_id)
+            .collect::<Vec<_>>();
+
+        for m in self.mod_manager.loaded_mods.all_items_mut() {
+            m.enabled = ids.contains(&m.id);
+        }
+
+        let missing = ids
+            .iter()
+            .filter(|id| self.mod_manager.find_mod(**id).is_none())
+            .count();
+
+        self.mod_manager.config.get_connections_mut()[index].mods = ids;
+        self.mod_manager.config.save()?;
+
+        Ok(missing)
+    }
+
+    fn render_querying(&self, stdout: &mut Stdout, index: usize) -> AppResult<()> {
+        let connection = &self.mod_manager.config.get_connections()[index];
+
+        self.clear_screen(stdout)?;
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 0),
+            PrintStyledContent(self.theme.paint(
+                Role::Title,
+                &format!("Arma 3 Mod Manager CLI - {}", connection.name)
+            )),
+            cursor::MoveTo(0, 2),
+            Print(&format!(
+                "Querying {}:{} ...",
+                connection.host,
+                connection.get_query_port()
+            )),
+        )?;
+
+        stdout.flush()?;
+
+        Ok(())
+    }
+
+    fn render_query_screen(
+        &self,
+        stdout: &mut Stdout,
+        index: usize,
+        info: Option<&ServerInfo>,
+        rules: &ArmaRules,
+        scroll: usize,
+        message: &Option<(Role, String)>,
+    ) -> AppResult<()> {
+        let connection = &self.mod_manager.config.get_connections()[index];
+
+        self.clear_screen(stdout)?;
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 0),
+            PrintStyledContent(self.theme.paint(
+                Role::Title,
+                &format!("Arma 3 Mod Manager CLI - {}", connection.name)
+            )),
+        )?;
+
+        if let Some((role, text)) = message {
+            execute!(
+                stdout,
+                cursor::MoveTo(0, 1),
+                PrintStyledContent(self.theme.paint(*role, text)),
+            )?;
+        }
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 2),
+            Print("<A> Enable Required Mods  <R> Query Again  <ESC> Back"),
+        )?;
+
+        let Some(info) = info else {
+            stdout.flush()?;
+            return Ok(());
+        };
+
+        let missing = rules
+            .mods
+            .iter()
+            .filter(|m| !m.dlc && self.mod_manager.find_mod(m.workshop_id).is_none())
+            .count();
+
+        let details = [
+            ("Name", info.name.clone()),
+            ("Map", info.map.clone()),
+            ("Mission", info.mission.clone()),
+            ("Players", format!("{}/{}", info.players, info.max_players)),
+            (
+                "Version",
+                format!(
+                    "{}{}",
+                    info.version,
+                    if info.password { "  (password)" } else { "" }
+                ),
+            ),
+            (
+                "Mods",
+                format!(
+                    "{} required, {} missing, {} signature keys",
+                    rules.mods.len(),
+                    missing,
+                    rules.signatures.len()
+                ),
+            ),
+        ];
+
+        let mut top_offset = 4;
+
+        for (label, value) in details {
+            execute!(
+                stdout,
+                cursor::MoveTo(3, top_offset),
+                Print(&format!("{:<12}{}", label, value)),
+            )?;
+            top_offset += 1;
+        }
+
+        top_offset += 1;
+
+        let (_, height) = terminal::size()?;
+        let visible = (height.saturating_sub(top_offset + 1) as usize).max(1);
+
+        for m in rules.mods.iter().skip(scroll).take(visible) {
+            let installed = self.mod_manager.find_mod(m.workshop_id);
+
+            let (role, state) = match installed {
+                _ if m.dlc => (Role::Muted, "DLC"),
+                Some(i_mod) if i_mod.enabled => (Role::Enabled, "[X]"),
+                Some(_) => (Role::Disabled, "[ ]"),
+                None => (Role::Missing, "Missing"),
+            };
+
+            execute!(
+                stdout,
+                cursor::MoveTo(3, top_offset),
+                PrintStyledContent(self.theme.paint(
+                    role,
+                    &format!(
+                        "{:<9}{} {:<14}{:08x}",
+                        state,
+                        text::pad(&m.name, 39),
+                        m.workshop_id,
+                        m.hash
+                    )
+                )),
+            )?;
+            top_offset += 1;
+        }
+
+        stdout.flush()?;
+
+        Ok(())
+    }
+}
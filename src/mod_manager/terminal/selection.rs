@@ -0,0 +1,208 @@
+use std::io::Stdout;
+
+use regex::RegexBuilder;
+
+use crate::errors::{AppError, AppResult};
+
+use super::{
+    super::{server::ModSide, theme::Role, Mod},
+    Terminal,
+};
+
+// What a bulk action does to each mod it is applied to
+#[derive(Debug, Clone, Copy)]
+pub(super) enum BulkAction {
+    Enable,
+    Disable,
+    Invert,
+}
+
+impl Terminal<'_> {
+    pub(super) fn toggle_mark(&mut self) {
+        if let Some(id) = self.selected_mod().map(|m| m.id) {
+            if !self.marked.remove(&id) {
+                self.marked.insert(id);
+            }
+        }
+    }
+
+    // Starts a visual range at the cursor, or ends it and keeps the range marked
+    pub(super) fn toggle_visual(&mut self) {
+        let Some(anchor) = self.visual_anchor.take() else {
+            self.visual_anchor = Some(self.mod_manager.loaded_mods.cursor());
+            return;
+        };
+
+        let range = self.visual_range(anchor);
+        let ids = self.mod_manager.loaded_mods.all_items()[range]
+            .iter()
+            .map(|m| m.id)
+            .collect::<Vec<_>>();
+
+        self.marked.extend(ids);
+    }
+
+    pub(super) fn has_selection(&self) -> bool {
+        self.visual_anchor.is_some() || !self.marked.is_empty()
+    }
+
+    pub(super) fn clear_selection(&mut self) {
+        self.visual_anchor = None;
+        self.marked.clear();
+    }
+
+    // Whether the mod at the index is marked or inside the visual range
+    pub(super) fn is_selected(&self, index: usize, id: u64) -> bool {
+        self.marked.contains(&id)
+            || self
+                .visual_anchor
+                .is_some_and(|anchor| self.visual_range(anchor).contains(&index))
+    }
+
+    // The mods a bulk action applies to, the mod under the cursor when none are selected
+    fn selected_ids(&self) -> Vec<u64> {
+        if !self.has_selection() {
+            return self.selected_mod().map(|m| m.id).into_iter().collect();
+        }
+
+        self.mod_manager
+            .loaded_mods
+            .all_items()
+            .iter()
+            .enumerate()
+            .filter(|(index, m)| self.is_selected(*index, m.id))
+            .map(|(_, m)| m.id)
+            .collect()
+    }
+
+    fn visual_range(&self, anchor: usize) -> std::ops::RangeInclusive<usize> {
+        let cursor = self.mod_manager.loaded_mods.cursor();
+        let last = self
+            .mod_manager
+            .loaded_mods
+            .all_items()
+            .len()
+            .saturating_sub(1);
+
+        anchor.min(cursor).min(last)..=anchor.max(cursor).min(last)
+    }
+
+    pub(super) fn apply_to_selection(&mut self, action: BulkAction) {
+        let ids = self.selected_ids();
+        self.set_enabled(&ids, action);
+        self.clear_selection();
+    }
+
+    // Adds a tag to the selected mods, or removes it when it starts with -
+    pub(super) fn tag_selection(&mut self, stdout: &mut Stdout) -> AppResult<()> {
+        let Some(input) = self.prompt(stdout, "Tag (-tag removes it): ")? else {
+            return Ok(());
+        };
+
+        let (remove, tag) = match input.trim().strip_prefix('-') {
+            Some(tag) => (true, tag.trim()),
+            None => (false, input.trim()),
+        };
+
+        // Tags are shown as #tag, so they can't contain spaces
+        let tag = tag.trim_start_matches('#').replace(' ', "-");
+
+        if tag.is_empty() {
+            return Ok(());
+        }
+
+        let ids = self.selected_ids();
+        let config = &mut self.mod_manager.config;
+
+        for id in &ids {
+            if remove {
+                config.remove_tag(*id, &tag);
+            } else {
+                config.add_tag(*id, &tag);
+            }
+        }
+
+        config.save()?;
+        self.clear_selection();
+
+        Ok(())
+    }
+
+    // Enables or disables every mod whose name or tags match a pattern
+    pub(super) fn apply_to_matching(
+        &mut self,
+        stdout: &mut Stdout,
+        action: BulkAction,
+    ) -> AppResult<()> {
+        let label = match action {
+            BulkAction::Enable => "Enable mods matching: ",
+            BulkAction::Disable => "Disable mods matching: ",
+            BulkAction::Invert => "Toggle mods matching: ",
+        };
+
+        let Some(pattern) = self.prompt(stdout, label)? else {
+            return Ok(());
+        };
+
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| AppError::InvalidPattern(e.to_string()))?;
+
+        let ids = self
+            .mod_manager
+            .loaded_mods
+            .all_items()
+            .iter()
+            .filter(|m| regex.is_match(&self.mod_label(m)))
+            .map(|m| m.id)
+            .collect::<Vec<_>>();
+
+        self.set_enabled(&ids, action);
+        self.status = Some((Role::Success, format!("{} mods matched", ids.len())));
+
+        Ok(())
+    }
+
+    // The name with the mod's tags, as shown in the list and matched by patterns
+    pub(super) fn mod_label(&self, m: &Mod) -> String {
+        let tags = self.mod_manager.config.get_tags(m.id);
+
+        if tags.is_empty() {
+            return m.name.clone();
+        }
+
+        format!("{} #{}", m.name, tags.join(" #"))
+    }
+
+    // Applies the action to the mods, or to their place in the profile being edited
+    fn set_enabled(&mut self, ids: &[u64], action: BulkAction) {
+        if let Some(profile) = self.editing_profile_mut() {
+            for id in ids {
+                let enabled = profile.mods.contains_key(id);
+                let enable = match action {
+                    BulkAction::Enable => true,
+                    BulkAction::Disable => false,
+                    BulkAction::Invert => !enabled,
+                };
+
+                // Mods already in the profile keep the side they were given
+                if enable != enabled {
+                    profile.set_side(*id, enable.then_some(ModSide::Both));
+                }
+            }
+
+            return;
+        }
+
+        for id in ids {
+            if let Some(m) = self.mod_manager.find_mod_mut(*id) {
+                m.enabled = match action {
+                    BulkAction::Enable => true,
+                    BulkAction::Disable => false,
+                    BulkAction::Invert => !m.enabled,
+                };
+            }
+        }
+    }
+}
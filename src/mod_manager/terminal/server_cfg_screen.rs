@@ -0,0 +1,195 @@
+use std::io::{Stdout, Write};
+
+use crossterm::{
+    cursor::{self, SetCursorStyle},
+    event::{self, Event, KeyCode, KeyEvent},
+    execute,
+    style::{Print, PrintStyledContent},
+};
+
+use crate::{errors::AppResult, mod_manager::server_cfg::CfgField};
+
+use super::{super::theme::Role, Terminal};
+
+impl Terminal<'_> {
+    pub(super) fn server_cfg_screen(&mut self, stdout: &mut Stdout, index: usize) -> AppResult<()> {
+        let mut selected_index = 0;
+        // The field being edited and its text so far
+        let mut editing: Option<String> = None;
+        let mut message: Option<(Role, String)> = None;
+
+        loop {
+            self.render_server_cfg_screen(
+                stdout,
+                index,
+                selected_index,
+                editing.as_deref(),
+                &message,
+            )?;
+
+            let Event::Key(KeyEvent { code, .. }) = event::read()? else {
+                continue;
+            };
+
+            let field = CfgField::ALL[selected_index];
+            let profile = &mut self.mod_manager.config.get_server_profiles_mut()[index];
+
+            if let Some(value) = &mut editing {
+                match code {
+                    KeyCode::Enter => {
+                        message = match profile.settings.set(field, value) {
+                            Ok(()) => None,
+                            Err(e) => Some((Role::Error, e.to_string())),
+                        };
+                        editing = None;
+                    }
+                    KeyCode::Esc => editing = None,
+                    KeyCode::Backspace => {
+                        value.pop();
+                    }
+                    KeyCode::Char(c) => value.push(c),
+                    _ => {}
+                }
+
+                if editing.is_none() {
+                    execute!(stdout, cursor::Hide, SetCursorStyle::DefaultUserShape)?;
+                }
+                continue;
+            }
+
+            match code {
+                KeyCode::Char('k') | KeyCode::Char('w') | KeyCode::Up => {
+                    selected_index = selected_index.saturating_sub(1);
+                }
+                KeyCode::Char('j') | KeyCode::Char('s') | KeyCode::Down
+                    if selected_index + 1 < CfgField::ALL.len() =>
+                {
+                    selected_index += 1;
+                }
+                KeyCode::Enter if field.is_bool() => {
+                    let value = !profile.settings.get(field).parse::<bool>().unwrap_or(false);
+                    profile.settings.set(field, &value.to_string())?;
+                }
+                KeyCode::Enter => {
+                    editing = Some(profile.settings.get(field));
+                    execute!(stdout, cursor::Show, SetCursorStyle::BlinkingUnderScore)?;
+                }
+                KeyCode::Char('i') => {
+                    message = Some(match profile.import_cfg_files() {
+                        Ok(()) => (
+                            Role::Success,
+                            "Imported server.cfg and basic.cfg".to_string(),
+                        ),
+                        Err(e) => (Role::Error, e.to_string()),
+                    });
+                }
+                KeyCode::Char('g') => {
+                    message = Some(match profile.write_cfg_files() {
+                        Ok(()) => (
+                            Role::Success,
+                            format!("Wrote {}", profile.get_server_cfg_path().to_string_lossy()),
+                        ),
+                        Err(e) => (Role::Error, e.to_string()),
+                    });
+                }
+                KeyCode::Esc | KeyCode::Char('q') => break,
+                _ => {}
+            }
+        }
+
+        self.mod_manager.config.save()?;
+
+        Ok(())
+    }
+
+    fn render_server_cfg_screen(
+        &self,
+        stdout: &mut Stdout,
+        index: usize,
+        selected_index: usize,
+        editing: Option<&str>,
+        message: &Option<(Role, String)>,
+    ) -> AppResult<()> {
+        self.clear_screen(stdout)?;
+
+        let profile = &self.mod_manager.config.get_server_profiles()[index];
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 0),
+            PrintStyledContent(self.theme.paint(
+                Role::Title,
+                &format!("Arma 3 Mod Manager CLI - {}", profile.name)
+            )),
+        )?;
+
+        if let Some((role, message)) = message {
+            execute!(
+                stdout,
+                cursor::MoveTo(0, 1),
+                PrintStyledContent(self.theme.paint(*role, message)),
+            )?;
+        }
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 2),
+            Print("<ENTER> Edit  <G> Generate Files  <I> Import Files  <ESC> Back"),
+        )?;
+
+        let label_padding = 26;
+        let top_offset = 4;
+        let mut cursor_pos = None;
+
+        for (i, field) in CfgField::ALL.iter().enumerate() {
+            let y = top_offset + i as u16;
+            let cursor = if i == selected_index { " > " } else { "   " };
+
+            let value = match editing {
+                Some(value) if i == selected_index => {
+                    cursor_pos = Some((3 + label_padding + value.chars().count()) as u16);
+                    if field.is_secret() {
+                        "*".repeat(value.chars().count())
+                    } else {
+                        value.to_string()
+                    }
+                }
+                _ if field.is_secret() => "*".repeat(profile.settings.get(*field).len()),
+                _ => profile.settings.get(*field),
+            };
+
+            execute!(
+                stdout,
+                cursor::MoveTo(0, y),
+                PrintStyledContent(self.theme.paint(Role::Cursor, cursor)),
+                cursor::MoveTo(3, y),
+                Print(&format!(
+                    "{:<padding$}{}",
+                    field.label(),
+                    value,
+                    padding = label_padding
+                )),
+            )?;
+        }
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, top_offset + CfgField::ALL.len() as u16 + 1),
+            PrintStyledContent(self.theme.paint(
+                Role::Muted,
+                "Separate mission templates and MOTD lines with |"
+            )),
+        )?;
+
+        if let Some(x) = cursor_pos {
+            execute!(
+                stdout,
+                cursor::MoveTo(x, top_offset + selected_index as u16)
+            )?;
+        }
+
+        stdout.flush()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,552 @@
+use std::{
+    io::{Stdout, Write},
+    time::Duration,
+};
+
+use crossterm::{
+    cursor::{self, SetCursorStyle},
+    event::{self, Event, KeyCode, KeyEvent},
+    execute,
+    style::{Print, PrintStyledContent},
+};
+
+use crate::{
+    errors::{AppError, AppResult},
+    mod_manager::{
+        keys::KeyReport,
+        process::{self, TrackedProcess},
+        server::{ModSide, ProfileField, ServerProfile},
+        utils,
+    },
+};
+
+use super::{super::theme::Role, Terminal};
+
+impl Terminal<'_> {
+    pub(super) fn servers_screen(&mut self, stdout: &mut Stdout) -> AppResult<()> {
+        let mut selected_index = 0;
+        let mut message: Option<(Role, String)> = None;
+
+        loop {
+            self.check_processes()?;
+            self.render_servers_screen(stdout, selected_index, &message)?;
+
+            if !event::poll(Duration::from_millis(1000))? {
+                continue;
+            }
+
+            let profiles_len = self.mod_manager.config.get_server_profiles().len();
+
+            if let Event::Key(KeyEvent { code, .. }) = event::read()? {
+                match code {
+                    KeyCode::Char('k') | KeyCode::Char('w') | KeyCode::Up => {
+                        selected_index = selected_index.saturating_sub(1);
+                    }
+                    KeyCode::Char('j') | KeyCode::Char('s') | KeyCode::Down
+                        if selected_index + 1 < profiles_len =>
+                    {
+                        selected_index += 1;
+                    }
+                    KeyCode::Char('n') => {
+                        let profiles = self.mod_manager.config.get_server_profiles();
+                        let taken = profiles.iter().map(|p| p.name.as_str()).collect::<Vec<_>>();
+                        let name = utils::unused_name("Server", &taken);
+
+                        let profile =
+                            ServerProfile::new(name, self.mod_manager.config.get_game_path());
+
+                        self.mod_manager
+                            .config
+                            .get_server_profiles_mut()
+                            .push(profile);
+                        self.mod_manager.config.save()?;
+
+                        selected_index = profiles_len;
+                    }
+                    KeyCode::Delete if selected_index < profiles_len => {
+                        let profile = self
+                            .mod_manager
+                            .config
+                            .get_server_profiles_mut()
+                            .remove(selected_index);
+                        self.mod_manager.config.save()?;
+
+                        if let Some(mut server) = self.servers.remove(&profile.name) {
+                            server.kill()?;
+                        }
+                        self.stop_headless_clients(&profile.name)?;
+
+                        selected_index = selected_index.saturating_sub(1);
+                    }
+                    KeyCode::Char('e') if selected_index < profiles_len => {
+                        // Pick the profile's mods in the regular mod list
+                        self.editing_profile = Some(selected_index);
+                        break;
+                    }
+                    KeyCode::Char('c') if selected_index < profiles_len => {
+                        self.server_cfg_screen(stdout, selected_index)?;
+                    }
+                    KeyCode::Char('p') if selected_index < profiles_len => {
+                        self.profile_form_screen(stdout, selected_index)?;
+                    }
+                    KeyCode::Enter if selected_index < profiles_len => {
+                        // A wrong binary path is likely on a new profile, keep the screen open
+                        message = Some(match self.start_server(selected_index) {
+                            Ok(report) => self.key_report_message(&report),
+                            Err(e) => (Role::Error, e.to_string()),
+                        });
+                    }
+                    KeyCode::Char('b') if selected_index < profiles_len => {
+                        message = Some(match self.collect_keys(selected_index) {
+                            Ok(report) => self.key_report_message(&report),
+                            Err(e) => (Role::Error, e.to_string()),
+                        });
+                    }
+                    KeyCode::Char('h') if selected_index < profiles_len => {
+                        let name = self.mod_manager.config.get_server_profiles()[selected_index]
+                            .name
+                            .clone();
+
+                        let count = self.mod_manager.config.get_server_profiles()[selected_index]
+                            .headless_clients;
+
+                        if self.headless_clients.contains_key(&name) {
+                            self.stop_headless_clients(&name)?;
+                        } else if count == 0 {
+                            message = Some((
+                                Role::Warning,
+                                "Set the number of headless clients with <+> first".to_string(),
+                            ));
+                        } else if let Err(e) = self.start_headless_clients(selected_index) {
+                            message = Some((Role::Error, e.to_string()));
+                        }
+                    }
+                    KeyCode::Char('+') if selected_index < profiles_len => {
+                        self.mod_manager.config.get_server_profiles_mut()[selected_index]
+                            .headless_clients += 1;
+                        self.mod_manager.config.save()?;
+                    }
+                    KeyCode::Char('-') if selected_index < profiles_len => {
+                        let profile =
+                            &mut self.mod_manager.config.get_server_profiles_mut()[selected_index];
+                        profile.headless_clients = profile.headless_clients.saturating_sub(1);
+                        self.mod_manager.config.save()?;
+                    }
+                    KeyCode::Char('x') if selected_index < profiles_len => {
+                        let name =
+                            &self.mod_manager.config.get_server_profiles()[selected_index].name;
+
+                        if let Some(mut server) = self.servers.remove(name) {
+                            server.kill()?;
+                        }
+                    }
+                    KeyCode::Char('X') if selected_index < profiles_len => {
+                        let name = self.mod_manager.config.get_server_profiles()[selected_index]
+                            .name
+                            .clone();
+
+                        self.stop_headless_clients(&name)?;
+                    }
+                    KeyCode::Esc | KeyCode::Char('q') => break,
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn installed_mod_ids(&self) -> Vec<u64> {
+        self.mod_manager
+            .loaded_mods
+            .all_items()
+            .iter()
+            .map(|m| m.id)
+            .collect()
+    }
+
+    fn collect_keys(&self, index: usize) -> AppResult<KeyReport> {
+        let profile = &self.mod_manager.config.get_server_profiles()[index];
+
+        profile.collect_keys(
+            self.mod_manager.config.get_workshop_path(),
+            &self.installed_mod_ids(),
+            self.mod_manager.config.get_deploy_strategy(),
+        )
+    }
+
+    fn key_report_message(&self, report: &KeyReport) -> (Role, String) {
+        let text = format!(
+            "Collected {} keys, removed {}",
+            report.collected, report.removed
+        );
+
+        if report.missing.is_empty() {
+            return (Role::Success, text);
+        }
+
+        let names = report
+            .missing
+            .iter()
+            .map(|id| match self.mod_manager.find_mod(*id) {
+                Some(m) => m.name.clone(),
+                None => id.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        (
+            Role::Warning,
+            format!("{}, no key shipped by: {}", text, names),
+        )
+    }
+
+    // Starts the server after generating its configs and collecting the mod keys
+    fn start_server(&mut self, index: usize) -> AppResult<KeyReport> {
+        let profile = &mut self.mod_manager.config.get_server_profiles_mut()[index];
+
+        // The running server's configs are left as they are
+        if self.servers.contains_key(&profile.name) {
+            return Err(AppError::AlreadyRunning);
+        }
+
+        // Writing the files points the profile at them
+        if profile.manage_cfg {
+            profile.write_cfg_files()?;
+            self.mod_manager.config.save()?;
+        }
+
+        let profile = &self.mod_manager.config.get_server_profiles()[index];
+
+        let installed = self.installed_mod_ids();
+        let report = self.collect_keys(index)?;
+
+        let command = profile.build_command(
+            self.mod_manager.config.get_workshop_path(),
+            &installed,
+            self.mod_manager.config.get_deploy_strategy(),
+            self.mod_manager.config.get_lowercase_mods(),
+        )?;
+
+        let log_name = format!("server-{}", profile.name.replace(['/', ' '], "_"));
+        let server = TrackedProcess::spawn(command, &log_name)?;
+
+        self.status = None;
+        self.servers.insert(profile.name.clone(), server);
+
+        Ok(report)
+    }
+
+    // Starts the profile's headless clients with the mods enabled in the mod list
+    fn start_headless_clients(&mut self, index: usize) -> AppResult<()> {
+        let profile = &self.mod_manager.config.get_server_profiles()[index];
+
+        let mod_ids = self
+            .mod_manager
+            .loaded_mods
+            .filter(|m| m.enabled)
+            .iter()
+            .map(|m| m.id)
+            .collect::<Vec<_>>();
+
+        self.status = None;
+
+        // Each client is tracked as soon as it runs, so the ones started before a failure
+        // can still be stopped
+        for i in 0..profile.headless_clients {
+            let command = profile.build_headless_command(
+                self.mod_manager.config.get_workshop_path(),
+                &mod_ids,
+                i,
+            )?;
+
+            let log_name = format!("hc{}-{}", i + 1, profile.name.replace(['/', ' '], "_"));
+            let client = TrackedProcess::spawn(command, &log_name)?;
+
+            self.headless_clients
+                .entry(profile.name.clone())
+                .or_default()
+                .push(client);
+        }
+
+        Ok(())
+    }
+
+    fn profile_form_screen(&mut self, stdout: &mut Stdout, index: usize) -> AppResult<()> {
+        let mut selected_index = 0;
+        let mut editing: Option<String> = None;
+        let mut message: Option<(Role, String)> = None;
+
+        loop {
+            self.render_profile_form_screen(
+                stdout,
+                index,
+                selected_index,
+                editing.as_deref(),
+                &message,
+            )?;
+
+            let Event::Key(KeyEvent { code, .. }) = event::read()? else {
+                continue;
+            };
+
+            let field = ProfileField::ALL[selected_index];
+            let taken = self
+                .mod_manager
+                .config
+                .get_server_profiles()
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != index)
+                .map(|(_, p)| p.name.clone())
+                .collect::<Vec<_>>();
+            let profile = &mut self.mod_manager.config.get_server_profiles_mut()[index];
+
+            if let Some(value) = &mut editing {
+                match code {
+                    KeyCode::Enter => {
+                        let name = profile.name.clone();
+                        let result = profile.set_field(&taken, field, value);
+
+                        // Keep tracking a running server and its headless clients
+                        if profile.name != name {
+                            let renamed = profile.name.clone();
+
+                            if let Some(server) = self.servers.remove(&name) {
+                                self.servers.insert(renamed.clone(), server);
+                            }
+                            if let Some(clients) = self.headless_clients.remove(&name) {
+                                self.headless_clients.insert(renamed, clients);
+                            }
+                        }
+
+                        message = match result {
+                            Ok(()) => None,
+                            Err(e) => Some((Role::Error, e.to_string())),
+                        };
+                        editing = None;
+                    }
+                    KeyCode::Esc => editing = None,
+                    KeyCode::Backspace => {
+                        value.pop();
+                    }
+                    KeyCode::Char(c) => value.push(c),
+                    _ => {}
+                }
+
+                if editing.is_none() {
+                    execute!(stdout, cursor::Hide, SetCursorStyle::DefaultUserShape)?;
+                }
+                continue;
+            }
+
+            match code {
+                KeyCode::Char('k') | KeyCode::Char('w') | KeyCode::Up => {
+                    selected_index = selected_index.saturating_sub(1);
+                }
+                KeyCode::Char('j') | KeyCode::Char('s') | KeyCode::Down
+                    if selected_index + 1 < ProfileField::ALL.len() =>
+                {
+                    selected_index += 1;
+                }
+                KeyCode::Enter => {
+                    editing = Some(profile.get_field(field));
+                    execute!(stdout, cursor::Show, SetCursorStyle::BlinkingUnderScore)?;
+                }
+                KeyCode::Esc | KeyCode::Char('q') => break,
+                _ => {}
+            }
+        }
+
+        self.mod_manager.config.save()?;
+
+        Ok(())
+    }
+
+    fn stop_headless_clients(&mut self, name: &str) -> AppResult<()> {
+        if let Some(clients) = self.headless_clients.remove(name) {
+            for mut client in clients {
+                client.kill()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn render_servers_screen(
+        &self,
+        stdout: &mut Stdout,
+        selected_index: usize,
+        message: &Option<(Role, String)>,
+    ) -> AppResult<()> {
+        self.clear_screen(stdout)?;
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 0),
+            PrintStyledContent(self.theme.paint(Role::Title, "Arma 3 Mod Manager CLI")),
+        )?;
+
+        let message = match (&self.status, message) {
+            (Some((role, status)), _) => Some((*role, status.as_str())),
+            (None, Some((role, text))) => Some((*role, text.as_str())),
+            (None, None) => None,
+        };
+
+        if let Some((role, text)) = message {
+            execute!(
+                stdout,
+                cursor::MoveTo(0, 1),
+                PrintStyledContent(self.theme.paint(role, text)),
+            )?;
+        }
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 2),
+            Print("<ENTER> Start  <X> Stop  <P> Profile  <E> Edit Mods  <C> Server Config  <B> Collect Keys  <N> New  <DEL> Delete  <ESC> Back"),
+            cursor::MoveTo(0, 3),
+            Print("<H> Start/Stop Headless Clients  <SHIFT> + <X> Stop Headless Clients  <+>/<-> Headless Client Count"),
+        )?;
+
+        let profiles = self.mod_manager.config.get_server_profiles();
+
+        if profiles.is_empty() {
+            execute!(
+                stdout,
+                cursor::MoveTo(3, 5),
+                Print("No server profiles yet, press <N> to add one"),
+            )?;
+        }
+
+        let mut top_offset = 5;
+
+        for (i, profile) in profiles.iter().enumerate() {
+            let cursor = if i == selected_index { " > " } else { "   " };
+
+            let state = match self.servers.get(&profile.name) {
+                Some(server) => format!(
+                    "Running: PID {} ({})",
+                    server.pid(),
+                    process::format_elapsed(server.elapsed())
+                ),
+                None => "Stopped".to_string(),
+            };
+
+            let headless = match self.headless_clients.get(&profile.name) {
+                Some(clients) => format!(
+                    "Headless Clients: {}/{} running",
+                    clients.len(),
+                    profile.headless_clients
+                ),
+                None => format!("Headless Clients: {}", profile.headless_clients),
+            };
+
+            execute!(
+                stdout,
+                cursor::MoveTo(0, top_offset),
+                PrintStyledContent(self.theme.paint(Role::Cursor, cursor)),
+                cursor::MoveTo(3, top_offset),
+                Print(&format!(
+                    "{:<30}Port: {:<8}{}",
+                    profile.name, profile.port, state
+                )),
+                cursor::MoveTo(5, top_offset + 1),
+                PrintStyledContent(self.theme.paint(
+                    Role::Muted,
+                    &format!(
+                        "Mods: {}  Server Mods: {}  Client Mods: {}  {}  {}",
+                        profile.mod_ids(ModSide::Both).len(),
+                        profile.mod_ids(ModSide::Server).len(),
+                        profile.mod_ids(ModSide::Client).len(),
+                        headless,
+                        profile.binary_path
+                    )
+                )),
+            )?;
+
+            top_offset += 2;
+        }
+
+        stdout.flush()?;
+
+        Ok(())
+    }
+
+    fn render_profile_form_screen(
+        &self,
+        stdout: &mut Stdout,
+        index: usize,
+        selected_index: usize,
+        editing: Option<&str>,
+        message: &Option<(Role, String)>,
+    ) -> AppResult<()> {
+        self.clear_screen(stdout)?;
+
+        let profile = &self.mod_manager.config.get_server_profiles()[index];
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 0),
+            PrintStyledContent(self.theme.paint(
+                Role::Title,
+                &format!("Arma 3 Mod Manager CLI - {}", profile.name)
+            )),
+        )?;
+
+        if let Some((role, message)) = message {
+            execute!(
+                stdout,
+                cursor::MoveTo(0, 1),
+                PrintStyledContent(self.theme.paint(*role, message)),
+            )?;
+        }
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, 2),
+            Print("<ENTER> Edit  <ESC> Back"),
+        )?;
+
+        let label_padding = 18;
+        let top_offset = 4;
+        let mut cursor_pos = None;
+
+        for (i, field) in ProfileField::ALL.iter().enumerate() {
+            let y = top_offset + i as u16;
+            let cursor = if i == selected_index { " > " } else { "   " };
+
+            let value = match editing {
+                Some(value) if i == selected_index => {
+                    cursor_pos = Some((3 + label_padding + value.chars().count()) as u16);
+                    value.to_string()
+                }
+                _ => profile.get_field(*field),
+            };
+
+            execute!(
+                stdout,
+                cursor::MoveTo(0, y),
+                PrintStyledContent(self.theme.paint(Role::Cursor, cursor)),
+                cursor::MoveTo(3, y),
+                Print(&format!(
+                    "{:<padding$}{}",
+                    field.label(),
+                    value,
+                    padding = label_padding
+                )),
+            )?;
+        }
+
+        if let Some(x) = cursor_pos {
+            execute!(
+                stdout,
+                cursor::MoveTo(x, top_offset + selected_index as u16)
+            )?;
+        }
+
+        stdout.flush()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+// Fitting text into columns by how wide it is on screen, CJK characters take two columns
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+const ELLIPSIS: char = '…';
+
+// Ticks a long name rests at either end before scrolling on
+const SCROLL_PAUSE: usize = 3;
+
+pub(super) fn width(text: &str) -> usize {
+    text.width()
+}
+
+// Cuts the text to the width, ending it with an ellipsis when something was cut off
+pub(super) fn truncate(text: &str, width: usize) -> String {
+    if self::width(text) <= width {
+        return text.to_string();
+    }
+
+    let mut result = String::new();
+    let mut used = 0;
+
+    for c in text.chars() {
+        let char_width = c.width().unwrap_or(0);
+
+        if used + char_width + 1 > width {
+            break;
+        }
+
+        result.push(c);
+        used += char_width;
+    }
+
+    if width > 0 {
+        result.push(ELLIPSIS);
+    }
+
+    result
+}
+
+// Truncates or pads the text with spaces to exactly the width
+pub(super) fn pad(text: &str, width: usize) -> String {
+    let text = truncate(text, width);
+    let padding = width.saturating_sub(self::width(&text));
+
+    text + &" ".repeat(padding)
+}
+
+// The part of the text that fits the width, scrolled along by the tick when it doesn't fit
+pub(super) fn scroll(text: &str, width: usize, tick: usize) -> String {
+    let chars = text
+        .chars()
+        .map(|c| (c, c.width().unwrap_or(0)))
+        .collect::<Vec<_>>();
+
+    // The first character the window can start at and still be filled to the end
+    let mut max_start = chars.len();
+    let mut tail_width = 0;
+
+    while max_start > 0 && tail_width + chars[max_start - 1].1 <= width {
+        max_start -= 1;
+        tail_width += chars[max_start].1;
+    }
+
+    if max_start == 0 {
+        return text.to_string();
+    }
+
+    let start = (tick % (max_start + 2 * SCROLL_PAUSE + 1))
+        .saturating_sub(SCROLL_PAUSE)
+        .min(max_start);
+
+    let mut result = String::new();
+    let mut used = 0;
+
+    for &(c, char_width) in &chars[start..] {
+        if used + char_width > width {
+            break;
+        }
+
+        result.push(c);
+        used += char_width;
+    }
+
+    result
+}
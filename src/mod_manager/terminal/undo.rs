@@ -0,0 +1,52 @@
+use crate::{errors::AppResult, mod_manager::history::Snapshot};
+
+use super::{super::theme::Role, Terminal};
+
+impl Terminal<'_> {
+    // Adds the state before a key press to the history when the key changed it
+    pub(super) fn record_edit(&mut self, before: Snapshot) -> AppResult<()> {
+        if self.mod_manager.snapshot() == before {
+            return Ok(());
+        }
+
+        let limit = self.mod_manager.config.get_history_limit();
+        self.history.record(before, limit);
+
+        self.save_history()
+    }
+
+    // Goes back, or forward again, through the edits the number of times
+    pub(super) fn step_history(&mut self, undo: bool, repeat: usize) -> AppResult<()> {
+        for _ in 0..repeat {
+            let current = self.mod_manager.snapshot();
+
+            let snapshot = if undo {
+                self.history.undo(current)
+            } else {
+                self.history.redo(current)
+            };
+
+            let Some(snapshot) = snapshot else {
+                let message = if undo {
+                    "Nothing to undo"
+                } else {
+                    "Nothing to redo"
+                };
+                self.status = Some((Role::Warning, message.to_string()));
+                break;
+            };
+
+            self.mod_manager.restore(&snapshot)?;
+        }
+
+        self.save_history()
+    }
+
+    fn save_history(&self) -> AppResult<()> {
+        if self.mod_manager.config.get_keep_history() {
+            self.history.save()?;
+        }
+
+        Ok(())
+    }
+}
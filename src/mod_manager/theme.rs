@@ -0,0 +1,190 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use crossterm::style::{Attribute, Color, Colored, ContentStyle, StyledContent};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{AppError, AppResult};
+
+pub const DEFAULT_THEME: &str = "dark";
+
+// What a piece of text on screen is, each is drawn in the theme's style for it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    // Titles, headings and the help
+    Title,
+    // Secondary text, like hints and counts
+    Muted,
+    // The scrollbar
+    Faint,
+    // The > in front of the selected row
+    Cursor,
+    // Marked mods and the visual range
+    Selection,
+    Success,
+    Warning,
+    Error,
+    // Mods in the list, or required by a server
+    Enabled,
+    Disabled,
+    Missing,
+    Unsigned,
+    BadSignature,
+    // Enabled mods the game log found missing a dependency
+    Conflict,
+    // Enabled mods Steam updated while the game runs the old version
+    Outdated,
+    New,
+    Updated,
+}
+
+impl Role {
+    // Without colour these are bold, so they still stand out
+    fn is_emphasized(self) -> bool {
+        matches!(
+            self,
+            Role::Title
+                | Role::Error
+                | Role::Warning
+                | Role::Enabled
+                | Role::Missing
+                | Role::BadSignature
+                | Role::Conflict
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct Theme {
+    colors: BTreeMap<Role, Color>,
+    monochrome: bool,
+}
+
+impl Theme {
+    // The named theme with the colours from the config on top, or no colours at all
+    // when NO_COLOR is set or the theme is mono
+    pub fn new(name: &str, overrides: &BTreeMap<Role, String>) -> AppResult<Theme> {
+        let no_color = std::env::var("NO_COLOR").is_ok_and(|value| !value.is_empty());
+
+        if no_color || name == "mono" {
+            // Also keeps colour out of anything not drawn through the theme
+            Colored::set_ansi_color_disabled(true);
+
+            return Ok(Theme {
+                colors: BTreeMap::new(),
+                monochrome: true,
+            });
+        }
+
+        let mut colors = match name {
+            "dark" => dark(),
+            "light" => light(),
+            _ => return Err(AppError::InvalidTheme(name.to_string())),
+        };
+
+        for (role, color) in overrides {
+            colors.insert(*role, parse_color(color)?);
+        }
+
+        Ok(Theme {
+            colors,
+            monochrome: false,
+        })
+    }
+
+    // Mod states are told apart by markers instead of colour
+    pub fn is_monochrome(&self) -> bool {
+        self.monochrome
+    }
+
+    pub fn paint<D: Display>(&self, role: Role, content: D) -> StyledContent<D> {
+        let mut style = ContentStyle::new();
+
+        if self.monochrome {
+            if role.is_emphasized() {
+                style.attributes.set(Attribute::Bold);
+            }
+        } else {
+            style.foreground_color = self.colors.get(&role).copied();
+        }
+
+        StyledContent::new(style, content)
+    }
+}
+
+// For dark backgrounds, the colours the manager always had
+fn dark() -> BTreeMap<Role, Color> {
+    BTreeMap::from([
+        (Role::Title, Color::Cyan),
+        (Role::Muted, Color::Grey),
+        (Role::Faint, Color::DarkGrey),
+        (Role::Cursor, Color::Red),
+        (Role::Selection, Color::Magenta),
+        (Role::Success, Color::Green),
+        (Role::Warning, Color::Yellow),
+        (Role::Error, Color::Red),
+        (Role::Enabled, Color::White),
+        (Role::Disabled, Color::Grey),
+        (Role::Missing, Color::Red),
+        (Role::Unsigned, Color::Yellow),
+        (Role::BadSignature, Color::Red),
+        (Role::Conflict, Color::AnsiValue(208)),
+        (Role::Outdated, Color::Blue),
+        (Role::New, Color::Green),
+        (Role::Updated, Color::Cyan),
+    ])
+}
+
+// For light backgrounds, where white and the bright colours are hard to read
+fn light() -> BTreeMap<Role, Color> {
+    BTreeMap::from([
+        (Role::Title, Color::DarkBlue),
+        (Role::Muted, Color::DarkGrey),
+        (Role::Faint, Color::Grey),
+        (Role::Cursor, Color::DarkRed),
+        (Role::Selection, Color::DarkMagenta),
+        (Role::Success, Color::DarkGreen),
+        (Role::Warning, Color::DarkYellow),
+        (Role::Error, Color::DarkRed),
+        (Role::Enabled, Color::Black),
+        (Role::Disabled, Color::DarkGrey),
+        (Role::Missing, Color::DarkRed),
+        (Role::Unsigned, Color::DarkYellow),
+        (Role::BadSignature, Color::DarkRed),
+        (Role::Conflict, Color::AnsiValue(130)),
+        (Role::Outdated, Color::DarkBlue),
+        (Role::New, Color::DarkGreen),
+        (Role::Updated, Color::DarkCyan),
+    ])
+}
+
+// A colour name like dark_red, default for the terminal's own colour, #rrggbb or 0-255
+fn parse_color(text: &str) -> AppResult<Color> {
+    let invalid = || AppError::InvalidColor(text.to_string());
+    let text = text.trim();
+
+    if let Some(hex) = text.strip_prefix('#') {
+        if hex.len() != 6 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+
+        return Ok(Color::Rgb {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+        });
+    }
+
+    if let Ok(value) = text.parse::<u8>() {
+        return Ok(Color::AnsiValue(value));
+    }
+
+    match text.to_lowercase().replace(['-', ' '], "_").as_str() {
+        "default" => Ok(Color::Reset),
+        "gray" => Ok(Color::Grey),
+        "dark_gray" => Ok(Color::DarkGrey),
+        name => Color::try_from(name).map_err(|_| invalid()),
+    }
+}
@@ -1,4 +1,11 @@
-use std::{env, ffi::OsString, path::Path};
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
 
 use crate::errors::{AppError, AppResult};
 
@@ -11,7 +18,7 @@ pub fn get_home_path() -> AppResult<OsString> {
 
 pub fn setup_steam_paths() -> AppResult<(String, String)> {
     let home_path = get_home_path()?;
-    
+
     // Define OS-specific base paths
     let base_path = match std::env::consts::OS {
         "macos" => Path::new(&home_path).join("Library/Application Support"),
@@ -30,6 +37,19 @@ pub fn setup_steam_paths() -> AppResult<(String, String)> {
     Ok((workshop_path, game_path))
 }
 
+// Names come from meta.cpp files and servers, control characters in them would garble the terminal
+pub fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .filter_map(|c| match c {
+            '\t' | '\n' | '\r' => Some(' '),
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
 fn construct_path(base_path: &Path, relative_path: &str) -> AppResult<String> {
     let full_path = base_path.join(relative_path);
     full_path
@@ -37,3 +57,29 @@ fn construct_path(base_path: &Path, relative_path: &str) -> AppResult<String> {
         .ok_or_else(|| AppError::PathConversionError(full_path.to_string_lossy().into()))
         .map(|s| s.to_string())
 }
+
+// The first of "Server 1", "Server 2", ... that isn't taken yet, for new server profiles and
+// connections, which are told apart by name
+pub fn unused_name(base: &str, taken: &[&str]) -> String {
+    (1..)
+        .map(|n| format!("{} {}", base, n))
+        .find(|name| !taken.contains(&name.as_str()))
+        .unwrap_or_default()
+}
+
+// Resolves the executable inside a macOS .app bundle from its Info.plist
+//
+// Running it directly instead of through `open` gives us the game's own PID and output
+pub fn get_bundle_executable(app_path: &Path) -> AppResult<PathBuf> {
+    let plist_path = app_path.join("Contents/Info.plist");
+
+    let plist = fs::read_to_string(&plist_path)
+        .map_err(|_| AppError::InvalidPath(plist_path.to_string_lossy().into()))?;
+
+    Regex::new(r"<key>CFBundleExecutable</key>\s*<string>([^<]+)</string>")
+        .unwrap()
+        .captures(&plist)
+        .and_then(|caps| caps.get(1))
+        .map(|m| app_path.join("Contents/MacOS").join(m.as_str()))
+        .ok_or_else(|| AppError::InvalidPath(plist_path.to_string_lossy().into()))
+}
@@ -0,0 +1,291 @@
+use crate::errors::{AppError, AppResult};
+
+// A node of Valve's KeyValues text format, as used by Steam's .acf and .vdf files
+//
+// Objects keep their order so a file can be written back the way Steam wrote it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VdfNode {
+    Value(String),
+    Object(Vec<(String, VdfNode)>),
+}
+
+impl VdfNode {
+    pub fn get(&self, key: &str) -> Option<&VdfNode> {
+        match self {
+            VdfNode::Object(entries) => entries
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v),
+            VdfNode::Value(_) => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut VdfNode> {
+        match self {
+            VdfNode::Object(entries) => entries
+                .iter_mut()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v),
+            VdfNode::Value(_) => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            VdfNode::Value(value) => Some(value),
+            VdfNode::Object(_) => None,
+        }
+    }
+
+    // Removes the key from an object, returns whether it was there
+    pub fn remove(&mut self, key: &str) -> bool {
+        match self {
+            VdfNode::Object(entries) => {
+                let len = entries.len();
+                entries.retain(|(k, _)| !k.eq_ignore_ascii_case(key));
+                entries.len() != len
+            }
+            VdfNode::Value(_) => false,
+        }
+    }
+
+    // Parses a file, which is a single root key with its object
+    pub fn parse(text: &str) -> AppResult<(String, VdfNode)> {
+        let mut tokens = tokenize(text)?.into_iter();
+
+        let Some(Token::String(key)) = tokens.next() else {
+            return Err(parse_error("expected the root key"));
+        };
+        let Some(Token::Open) = tokens.next() else {
+            return Err(parse_error("expected { after the root key"));
+        };
+
+        Ok((key, parse_object(&mut tokens)?))
+    }
+
+    pub fn to_vdf(&self, key: &str) -> String {
+        let mut text = String::new();
+        write_entry(&mut text, key, self, 0);
+        text
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    String(String),
+    Open,
+    Close,
+}
+
+fn tokenize(text: &str) -> AppResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => tokens.push(Token::Open),
+            '}' => tokens.push(Token::Close),
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some(c) => value.push(c),
+                            None => return Err(parse_error("unterminated string")),
+                        },
+                        Some(c) => value.push(c),
+                        None => return Err(parse_error("unterminated string")),
+                    }
+                }
+                tokens.push(Token::String(value));
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            c if c.is_whitespace() => {}
+            // Unquoted tokens are allowed too
+            c => {
+                let mut value = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '{' || c == '}' || c == '"' {
+                        break;
+                    }
+                    value.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::String(value));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn parse_object<I: Iterator<Item = Token>>(tokens: &mut I) -> AppResult<VdfNode> {
+    let mut entries = Vec::new();
+
+    loop {
+        match tokens.next() {
+            Some(Token::Close) => return Ok(VdfNode::Object(entries)),
+            Some(Token::String(key)) => {
+                let node = match tokens.next() {
+                    Some(Token::String(value)) => VdfNode::Value(value),
+                    Some(Token::Open) => parse_object(tokens)?,
+                    _ => return Err(parse_error(&format!("expected a value for {}", key))),
+                };
+                entries.push((key, node));
+            }
+            Some(Token::Open) => return Err(parse_error("unexpected {")),
+            None => return Err(parse_error("missing }")),
+        }
+    }
+}
+
+fn write_entry(text: &mut String, key: &str, node: &VdfNode, depth: usize) {
+    let indent = "\t".repeat(depth);
+
+    match node {
+        VdfNode::Value(value) => {
+            text.push_str(&format!(
+                "{}\"{}\"\t\t\"{}\"\n",
+                indent,
+                escape(key),
+                escape(value)
+            ));
+        }
+        VdfNode::Object(entries) => {
+            text.push_str(&format!("{}\"{}\"\n{}{{\n", indent, escape(key), indent));
+            for (key, node) in entries {
+                write_entry(text, key, node, depth + 1);
+            }
+            text.push_str(&format!("{}}}\n", indent));
+        }
+    }
+}
+
+// The inverse of what the tokenizer unescapes
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+fn parse_error(message: &str) -> AppError {
+    AppError::VdfParseError(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Shortened appworkshop_107410.acf as Steam writes it
+    const MANIFEST: &str = "\"AppWorkshop\"
+{
+\t\"appid\"\t\t\"107410\"
+\t\"SizeOnDisk\"\t\t\"1048576\"
+\t\"WorkshopItemsInstalled\"
+\t{
+\t\t\"450814997\"
+\t\t{
+\t\t\t\"size\"\t\t\"1048576\"
+\t\t\t\"timeupdated\"\t\t\"1700000000\"
+\t\t}
+\t}
+\t\"WorkshopItemDetails\"
+\t{
+\t}
+}
+";
+
+    #[test]
+    fn parses_a_manifest() {
+        let (key, root) = VdfNode::parse(MANIFEST).unwrap();
+
+        assert_eq!(key, "AppWorkshop");
+        assert_eq!(root.get("appid").and_then(|n| n.as_str()), Some("107410"));
+
+        let item = root
+            .get("workshopitemsinstalled")
+            .and_then(|n| n.get("450814997"))
+            .unwrap();
+        assert_eq!(item.get("size").and_then(|n| n.as_str()), Some("1048576"));
+        assert_eq!(
+            root.get("WorkshopItemDetails"),
+            Some(&VdfNode::Object(Vec::new()))
+        );
+    }
+
+    #[test]
+    fn writes_a_manifest_back_unchanged() {
+        let (key, root) = VdfNode::parse(MANIFEST).unwrap();
+
+        assert_eq!(root.to_vdf(&key), MANIFEST);
+    }
+
+    #[test]
+    fn round_trips_escaped_strings() {
+        let root = VdfNode::Object(vec![
+            (
+                "path".to_string(),
+                VdfNode::Value("C:\\Arma 3\\".to_string()),
+            ),
+            (
+                "title".to_string(),
+                VdfNode::Value("The \"Mod\"".to_string()),
+            ),
+            (
+                "description".to_string(),
+                VdfNode::Value("line\nnext\tcolumn".to_string()),
+            ),
+            (
+                "key with \"quotes\"".to_string(),
+                VdfNode::Object(Vec::new()),
+            ),
+        ]);
+
+        let text = root.to_vdf("root");
+
+        assert_eq!(VdfNode::parse(&text).unwrap(), ("root".to_string(), root));
+    }
+
+    #[test]
+    fn reads_comments_and_unquoted_tokens() {
+        let text = "// written by hand\nroot { key value // trailing\n nested { a \"b\" } }";
+        let (_, root) = VdfNode::parse(text).unwrap();
+
+        assert_eq!(root.get("key").and_then(|n| n.as_str()), Some("value"));
+        assert_eq!(
+            root.get("nested").and_then(|n| n.get("a")),
+            Some(&VdfNode::Value("b".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_broken_files() {
+        for text in [
+            "",
+            "\"root\"",
+            "\"root\" { \"key\" }",
+            "\"root\" { \"unterminated }",
+        ] {
+            assert!(VdfNode::parse(text).is_err(), "{}", text);
+        }
+    }
+}
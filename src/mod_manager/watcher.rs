@@ -0,0 +1,137 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Component, Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    time::{Duration, Instant},
+};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::errors::{AppError, AppResult};
+
+// Steam writes a mod over many seconds, so changes are only reported once it has gone quiet
+const SETTLE_TIME: Duration = Duration::from_secs(2);
+
+// Watches the workshop content folder for mods being added, updated or removed
+//
+// Only the folder itself, each mod folder and its addons are watched, watching every folder
+// of a large modset would run into the limit of inotify watches
+pub struct WorkshopWatcher {
+    workshop_path: PathBuf,
+    receiver: Receiver<notify::Result<Event>>,
+    watcher: RecommendedWatcher,
+    pending: HashSet<u64>,
+    last_event: Option<Instant>,
+}
+
+impl WorkshopWatcher {
+    pub fn new(workshop_path: &Path) -> AppResult<WorkshopWatcher> {
+        let (sender, receiver) = mpsc::channel();
+
+        let mut watcher = notify::recommended_watcher(sender).map_err(watch_error)?;
+        watcher
+            .watch(workshop_path, RecursiveMode::NonRecursive)
+            .map_err(watch_error)?;
+
+        let mut workshop_watcher = WorkshopWatcher {
+            workshop_path: workshop_path.to_path_buf(),
+            receiver,
+            watcher,
+            pending: HashSet::new(),
+            last_event: None,
+        };
+
+        for entry in fs::read_dir(workshop_path)?.filter_map(|e| e.ok()) {
+            workshop_watcher.watch_mod(&entry.path())?;
+        }
+
+        Ok(workshop_watcher)
+    }
+
+    // Watches the mod folder and its addons, where Steam puts the PBOs
+    fn watch_mod(&mut self, mod_path: &Path) -> AppResult<()> {
+        if !mod_path.is_dir() {
+            return Ok(());
+        }
+
+        self.watcher
+            .watch(mod_path, RecursiveMode::NonRecursive)
+            .map_err(watch_error)?;
+
+        let addons = fs::read_dir(mod_path)?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| {
+                p.is_dir()
+                    && p.file_name()
+                        .is_some_and(|n| n.eq_ignore_ascii_case("addons"))
+            });
+
+        for path in addons {
+            self.watcher
+                .watch(&path, RecursiveMode::NonRecursive)
+                .map_err(watch_error)?;
+        }
+
+        Ok(())
+    }
+
+    // Returns the IDs of the mods that changed, once no more changes came in for a while
+    pub fn poll(&mut self) -> Option<HashSet<u64>> {
+        while let Ok(event) = self.receiver.try_recv() {
+            let Ok(event) = event else {
+                continue;
+            };
+
+            // Reading a mod, like the signature checks do, is not a change
+            if matches!(event.kind, EventKind::Access(_)) {
+                continue;
+            }
+
+            for path in &event.paths {
+                if let Some(id) = self.mod_id(path) {
+                    self.pending.insert(id);
+                    self.last_event = Some(Instant::now());
+                }
+            }
+        }
+
+        let settled = self
+            .last_event
+            .is_some_and(|last_event| last_event.elapsed() >= SETTLE_TIME);
+
+        if !settled || self.pending.is_empty() {
+            return None;
+        }
+
+        self.last_event = None;
+        Some(std::mem::take(&mut self.pending))
+    }
+
+    // Watches the changed mods again, for new mods and ones whose addons folder was replaced
+    pub fn watch_mods(&mut self, ids: &HashSet<u64>) -> AppResult<()> {
+        for id in ids {
+            self.watch_mod(&self.workshop_path.join(id.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    // The ID of the mod folder the path is in
+    fn mod_id(&self, path: &Path) -> Option<u64> {
+        match path
+            .strip_prefix(&self.workshop_path)
+            .ok()?
+            .components()
+            .next()?
+        {
+            Component::Normal(name) => name.to_str()?.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+fn watch_error(e: notify::Error) -> AppError {
+    AppError::WatchError(e.to_string())
+}